@@ -1167,6 +1167,39 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "choose which RELRO level to use"),
     nll: bool = (false, parse_bool, [UNTRACKED],
                  "run the non-lexical lifetimes MIR pass"),
+    dump_nll_constraint_graph: bool = (false, parse_bool, [UNTRACKED],
+        "write a graphviz `.dot` file of the NLL region constraints for each function"),
+    nll_facts: bool = (false, parse_bool, [UNTRACKED],
+        "dump the facts computed by NLL as CSV files under `nll-facts/`"),
+    verify_nll_regions: bool = (false, parse_bool, [UNTRACKED],
+        "check that the solved NLL regions satisfy every outlives constraint"),
+    nll_stats: bool = (false, parse_bool, [UNTRACKED],
+        "print statistics about NLL region inference at the end of compilation"),
+    dump_liveness: bool = (false, parse_bool, [UNTRACKED],
+        "write the points at which each local is live, for each function, under `-Z dump-mir-dir`"),
+    nll_warn_unconstrained: bool = (false, parse_bool, [UNTRACKED],
+        "warn about lifetime parameters that NLL constraint generation does not constrain"),
+    nll_dump_filter: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "only dump NLL constraints of this category (`subtype`, `reborrow`, \
+         `interior-mutable-reborrow` or `seed`)"),
+    dump_nll_summary: bool = (false, parse_bool, [UNTRACKED],
+        "write the origin, live point count and blame span of each NLL region, for each function"),
+    dump_region_values: bool = (false, parse_bool, [UNTRACKED],
+        "write the final value of each NLL region, for each function, in a compact form"),
+    nll_max_iterations: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+        "report a compiler bug if NLL region inference takes more than this many iterations"),
+    nll_disable_reborrow_constraints: bool = (false, parse_bool, [UNTRACKED],
+        "do not generate NLL reborrow constraints, to see which errors depend on them"),
+    nll_conservative_reborrows: bool = (false, parse_bool, [UNTRACKED],
+        "treat every reborrow as mutable when generating NLL reborrow constraints"),
+    nll_report_hot_regions: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+        "warn about NLL regions that are live at more than this many points"),
+    nll_explain: bool = (false, parse_bool, [UNTRACKED],
+        "note the chain of outlives constraints behind each NLL region error"),
+    nll_verify_recompute: bool = (false, parse_bool, [UNTRACKED],
+        "check that recomputing the NLL regions one basic block at a time gives the same result"),
+    nll_verify_stream: bool = (false, parse_bool, [UNTRACKED],
+        "check that streaming the NLL constraints into a sink gives the same constraints"),
     trans_time_graph: bool = (false, parse_bool, [UNTRACKED],
         "generate a graphical HTML report of time spent in trans and LLVM"),
     thinlto: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -2722,6 +2755,38 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir_graphviz = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_nll_constraint_graph = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_facts = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.verify_nll_regions = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_stats = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_liveness = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_warn_unconstrained = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_dump_filter = Some(String::from("reborrow"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_nll_summary = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_region_values = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_max_iterations = Some(10);
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_disable_reborrow_constraints = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_conservative_reborrows = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_report_hot_regions = Some(100);
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_explain = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_verify_recompute = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_verify_stream = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
         // Make sure changing a [TRACKED] option changes the hash
         opts = reference.clone();
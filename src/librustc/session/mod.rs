@@ -155,6 +155,16 @@ pub struct PerfStats {
     pub symbol_hash_time: Cell<Duration>,
     /// The accumulated time spent decoding def path tables from metadata
     pub decode_def_path_tables_time: Cell<Duration>,
+    /// The number of MIR bodies whose regions were inferred by NLL
+    pub nll_bodies_count: Cell<u64>,
+    /// The total number of rounds NLL region propagation took to converge
+    pub nll_propagation_iterations: Cell<u64>,
+    /// The largest number of rounds NLL region propagation took for a single body
+    pub nll_max_propagation_iterations: Cell<u64>,
+    /// The accumulated time spent generating NLL liveness constraints
+    pub nll_liveness_constraints_time: Cell<Duration>,
+    /// The accumulated time spent generating NLL borrow constraints
+    pub nll_borrow_constraints_time: Cell<Duration>,
 }
 
 /// Enum to support dispatch of one-time diagnostics (in Session.diag_once)
@@ -620,6 +630,19 @@ impl Session {
                  duration_to_secs_str(self.perf_stats.decode_def_path_tables_time.get()));
     }
 
+    pub fn print_nll_stats(&self) {
+        println!("Total number of bodies checked by NLL:         {}",
+                 self.perf_stats.nll_bodies_count.get());
+        println!("Total number of NLL propagation iterations:    {}",
+                 self.perf_stats.nll_propagation_iterations.get());
+        println!("Maximum NLL propagation iterations per body:   {}",
+                 self.perf_stats.nll_max_propagation_iterations.get());
+        println!("Total time spent on NLL liveness constraints:  {}",
+                 duration_to_secs_str(self.perf_stats.nll_liveness_constraints_time.get()));
+        println!("Total time spent on NLL borrow constraints:    {}",
+                 duration_to_secs_str(self.perf_stats.nll_borrow_constraints_time.get()));
+    }
+
     /// We want to know if we're allowed to do an optimization for crate foo from -z fuel=foo=n.
     /// This expends fuel if applicable, and records fuel if applicable.
     pub fn consider_optimizing<T: Fn() -> String>(&self, crate_name: &str, msg: T) -> bool {
@@ -894,6 +917,11 @@ pub fn build_session_(sopts: config::Options,
             incr_comp_bytes_hashed: Cell::new(0),
             symbol_hash_time: Cell::new(Duration::from_secs(0)),
             decode_def_path_tables_time: Cell::new(Duration::from_secs(0)),
+            nll_bodies_count: Cell::new(0),
+            nll_propagation_iterations: Cell::new(0),
+            nll_max_propagation_iterations: Cell::new(0),
+            nll_liveness_constraints_time: Cell::new(Duration::from_secs(0)),
+            nll_borrow_constraints_time: Cell::new(Duration::from_secs(0)),
         },
         code_stats: RefCell::new(CodeStats::new()),
         optimization_fuel_crate,
@@ -24,7 +24,7 @@ use rustc::middle::cstore::CrateStore;
 use rustc::middle::privacy::AccessLevels;
 use rustc::ty::{self, TyCtxt, Resolutions, GlobalArenas};
 use rustc::traits;
-use rustc::util::common::{ErrorReported, time};
+use rustc::util::common::{ErrorReported, time, print_time_passes_entry};
 use rustc_allocator as allocator;
 use rustc_borrowck as borrowck;
 use rustc_incremental;
@@ -292,6 +292,10 @@ pub fn compile_input(sess: &Session,
         sess.print_perf_stats();
     }
 
+    if sess.opts.debugging_opts.nll_stats {
+        sess.print_nll_stats();
+    }
+
     controller_entry_point!(
         compilation_done,
         sess,
@@ -1071,6 +1075,16 @@ pub fn phase_3_run_analysis_passes<'tcx, F, R>(control: &CompileController,
              "MIR borrow checking",
              || for def_id in tcx.body_owners() { tcx.mir_borrowck(def_id) });
 
+        // NLL generates constraints once per body, so the time spent on
+        // each phase is accumulated in the perf stats and reported here
+        // once for the whole crate.
+        print_time_passes_entry(time_passes,
+                                "nll liveness constraints",
+                                sess.perf_stats.nll_liveness_constraints_time.get());
+        print_time_passes_entry(time_passes,
+                                "nll borrow constraints",
+                                sess.perf_stats.nll_borrow_constraints_time.get());
+
         time(time_passes,
              "MIR effect checking",
              || for def_id in tcx.body_owners() {
@@ -9,79 +9,321 @@
 // except according to those terms.
 
 use rustc::hir;
-use rustc::mir::{Location, Place, Mir, Rvalue};
+use rustc::mir::{BasicBlock, Constant, Location, Place, Mir, Rvalue, Terminator};
 use rustc::mir::visit::Visitor;
 use rustc::mir::Place::Projection;
 use rustc::mir::{PlaceProjection, ProjectionElem};
 use rustc::infer::InferCtxt;
 use rustc::traits::{self, ObligationCause};
-use rustc::ty::{self, Ty};
+use rustc::ty::{self, RegionVid, Ty, TypeFlags};
 use rustc::ty::fold::TypeFoldable;
-use rustc::util::common::ErrorReported;
-use rustc_data_structures::fx::FxHashSet;
-use syntax::codemap::DUMMY_SP;
+use rustc::util::common::{record_time, ErrorReported};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use std::mem;
+use syntax_pos::Span;
 
 use super::LivenessResults;
+use super::facts::AllFacts;
 use super::ToRegionVid;
-use super::region_infer::RegionInferenceContext;
+use super::region_infer::{ConstraintCategory, RegionInferenceContext};
 
-pub(super) fn generate_constraints<'a, 'gcx, 'tcx>(
+/// Receives the live points and outlives constraints produced by
+/// constraint generation. `RegionInferenceContext` stores them all for
+/// solving; other implementations may instead stream them into a more
+/// compact representation.
+pub trait ConstraintSink {
+    /// The region `v` is live at `point`.
+    fn add_live_point(&mut self, v: RegionVid, point: Location);
+
+    /// The region `sup` must outlive `sub` at `point`.
+    fn add_outlives(
+        &mut self,
+        span: Span,
+        category: ConstraintCategory,
+        sup: RegionVid,
+        sub: RegionVid,
+        point: Location,
+    );
+
+    /// The live points and constraints that follow (until the next
+    /// call) arise from `block`, or from no block in particular.
+    fn set_current_block(&mut self, _block: Option<BasicBlock>) {}
+}
+
+impl<'tcx> ConstraintSink for RegionInferenceContext<'tcx> {
+    fn add_live_point(&mut self, v: RegionVid, point: Location) {
+        RegionInferenceContext::add_live_point(self, v, point);
+    }
+
+    fn add_outlives(
+        &mut self,
+        span: Span,
+        category: ConstraintCategory,
+        sup: RegionVid,
+        sub: RegionVid,
+        point: Location,
+    ) {
+        RegionInferenceContext::add_outlives(self, span, category, sup, sub, point);
+    }
+
+    fn set_current_block(&mut self, block: Option<BasicBlock>) {
+        RegionInferenceContext::set_current_block(self, block);
+    }
+}
+
+pub(super) fn generate_constraints<'a, 'gcx, 'tcx, S: ConstraintSink>(
+    infcx: &InferCtxt<'a, 'gcx, 'tcx>,
+    sink: &mut S,
+    mir: &Mir<'tcx>,
+    body_id: hir::BodyId,
+    param_env: ty::ParamEnv<'gcx>,
+    liveness: &LivenessResults,
+    seed_constraints: &[(RegionVid, RegionVid, Location)],
+    all_facts: &mut Option<AllFacts>,
+) {
+    ConstraintGeneration::new(
+        infcx,
+        sink,
+        mir,
+        body_id,
+        param_env,
+        liveness,
+        seed_constraints,
+        all_facts,
+    ).add_constraints();
+}
+
+/// Like `generate_constraints`, but only generates the liveness and
+/// borrow constraints arising from the basic blocks in `blocks`. Seed
+/// constraints are not added again. This is used to regenerate the
+/// constraints of blocks whose contributions were removed with
+/// `RegionInferenceContext::retract_blocks`.
+pub(super) fn regenerate_constraints<'a, 'gcx, 'tcx>(
     infcx: &InferCtxt<'a, 'gcx, 'tcx>,
     regioncx: &mut RegionInferenceContext<'tcx>,
     mir: &Mir<'tcx>,
-    param_env: ty::ParamEnv<'tcx>,
+    body_id: hir::BodyId,
+    param_env: ty::ParamEnv<'gcx>,
     liveness: &LivenessResults,
+    blocks: &[BasicBlock],
 ) {
-    ConstraintGeneration {
+    ConstraintGeneration::new(
         infcx,
         regioncx,
         mir,
-        liveness,
+        body_id,
         param_env,
-    }.add_constraints();
+        liveness,
+        &[],
+        &mut None,
+    ).add_constraints_for_blocks(blocks);
 }
 
-struct ConstraintGeneration<'cx, 'gcx: 'tcx, 'tcx: 'cx> {
+pub(crate) struct ConstraintGeneration<'cx, 'gcx: 'tcx, 'tcx: 'cx, S: ConstraintSink + 'cx> {
     infcx: &'cx InferCtxt<'cx, 'gcx, 'tcx>,
-    regioncx: &'cx mut RegionInferenceContext<'tcx>,
+    sink: &'cx mut S,
     mir: &'cx Mir<'tcx>,
+    body_id: hir::BodyId,
     liveness: &'cx LivenessResults,
-    param_env: ty::ParamEnv<'tcx>,
+    param_env: ty::ParamEnv<'gcx>,
+
+    /// Additional `(sup, sub, point)` outlives constraints supplied by
+    /// the caller, which are added alongside the generated ones.
+    seed_constraints: &'cx [(RegionVid, RegionVid, Location)],
+
+    /// If `-Z nll-facts` is given, the facts we are gathering for it.
+    all_facts: &'cx mut Option<AllFacts>,
+
+    /// Results of `dtorck_constraint_for_ty`, keyed on the
+    /// (normalized) type being dropped. The same types tend to be
+    /// drop-live at many locations, so we only compute these once.
+    dtorck_cache: FxHashMap<Ty<'tcx>, ty::DtorckConstraint<'tcx>>,
+
+    /// The dropped types for which `dtorck_constraint_for_ty` reported
+    /// an overflow. We give up on their drop-liveness constraints, so
+    /// that the overflow is reported only once per type rather than
+    /// once per location at which it is drop-live.
+    overflowed_drop_tys: FxHashSet<Ty<'tcx>>,
+
+    /// In debug builds, the locations of the rvalues visited so far.
+    /// Visiting an rvalue twice would add its constraints twice, so
+    /// `visit_rvalue` asserts that each location is seen only once.
+    visited_rvalues: FxHashSet<Location>,
+
+    /// Buffers reused across calls to `add_drop_live_constraint`.
+    drop_scratch: DropScratch<'tcx>,
 }
 
-impl<'cx, 'gcx, 'tcx> ConstraintGeneration<'cx, 'gcx, 'tcx> {
+/// Scratch buffers for `add_drop_live_constraint`, which runs at every
+/// point where a local is drop-live. Keeping them between calls saves
+/// allocating them anew each time.
+#[derive(Default)]
+struct DropScratch<'tcx> {
+    /// The types still to be processed, with their depth.
+    types: Vec<(Ty<'tcx>, usize)>,
+
+    /// The types that have been pushed onto `types`.
+    known: FxHashSet<Ty<'tcx>>,
+
+    /// The types that have been normalized.
+    normalized: FxHashSet<Ty<'tcx>>,
+}
+
+impl<'cx, 'gcx, 'tcx, S: ConstraintSink + 'cx> ConstraintGeneration<'cx, 'gcx, 'tcx, S> {
+    /// Creates a context for generating the constraints of `mir` into
+    /// `sink`. Most callers want `generate_constraints` instead;
+    /// this allows the liveness and borrow phases to be driven
+    /// separately (e.g., with custom liveness results).
+    pub(crate) fn new(
+        infcx: &'cx InferCtxt<'cx, 'gcx, 'tcx>,
+        sink: &'cx mut S,
+        mir: &'cx Mir<'tcx>,
+        body_id: hir::BodyId,
+        param_env: ty::ParamEnv<'gcx>,
+        liveness: &'cx LivenessResults,
+        seed_constraints: &'cx [(RegionVid, RegionVid, Location)],
+        all_facts: &'cx mut Option<AllFacts>,
+    ) -> Self {
+        ConstraintGeneration {
+            infcx,
+            sink,
+            mir,
+            body_id,
+            liveness,
+            param_env,
+            seed_constraints,
+            all_facts,
+            dtorck_cache: FxHashMap(),
+            overflowed_drop_tys: FxHashSet(),
+            visited_rvalues: FxHashSet(),
+            drop_scratch: DropScratch::default(),
+        }
+    }
+
     fn add_constraints(&mut self) {
-        self.add_liveness_constraints();
-        self.add_borrow_constraints();
+        self.add_seed_constraints();
+        let blocks: Vec<_> = self.mir.basic_blocks().indices().collect();
+        self.add_constraints_for_blocks(&blocks);
+    }
+
+    /// Adds the liveness and borrow constraints arising from each
+    /// block in `blocks`. Everything added is tagged with the block it
+    /// came from, so that it can later be retracted. The time spent on
+    /// each phase is added to the session's perf stats, and reported
+    /// once for the whole crate by `-Z time-passes` and `-Z nll-stats`.
+    fn add_constraints_for_blocks(&mut self, blocks: &[BasicBlock]) {
+        let sess = self.infcx.tcx.sess;
+        record_time(&sess.perf_stats.nll_liveness_constraints_time, || {
+            self.add_liveness_constraints(blocks)
+        });
+        record_time(&sess.perf_stats.nll_borrow_constraints_time, || {
+            self.add_borrow_constraints(blocks)
+        });
+    }
+
+    /// Seed constraints:
+    ///
+    /// > For each caller-supplied `(sup, sub, point)`, `sup` must
+    /// > outlive `sub` at `point`.
+    fn add_seed_constraints(&mut self) {
+        debug!("add_seed_constraints()");
+        for &(sup, sub, point) in self.seed_constraints {
+            let span = self.mir.source_info(point).span;
+            self.sink
+                .add_outlives(span, ConstraintCategory::Seed, sup, sub, point);
+        }
     }
 
     /// Liveness constraints:
     ///
     /// > If a variable V is live at point P, then all regions R in the type of V
     /// > must include the point P.
-    fn add_liveness_constraints(&mut self) {
-        debug!("add_liveness_constraints()");
-        for bb in self.mir.basic_blocks().indices() {
-            debug!("add_liveness_constraints: bb={:?}", bb);
-
-            self.liveness
-                .regular
-                .simulate_block(self.mir, bb, |location, live_locals| {
-                    for live_local in live_locals.iter() {
-                        let live_local_ty = self.mir.local_decls[live_local].ty;
-                        self.add_regular_live_constraint(live_local_ty, location);
-                    }
-                });
-
-            self.liveness
-                .drop
-                .simulate_block(self.mir, bb, |location, live_locals| {
-                    for live_local in live_locals.iter() {
-                        let live_local_ty = self.mir.local_decls[live_local].ty;
-                        self.add_drop_live_constraint(live_local_ty, location);
-                    }
-                });
+    ///
+    /// Only the points in `blocks` are considered. The live points
+    /// added are tagged with the block they come from.
+    pub(crate) fn add_liveness_constraints(&mut self, blocks: &[BasicBlock]) {
+        // If no local needs to be dropped, then being drop-live never
+        // makes any region live, so we can skip simulating the
+        // drop-liveness results entirely.
+        let any_local_needs_drop = self.mir
+            .local_decls
+            .iter()
+            .any(|local_decl| self.needs_drop(local_decl.ty));
+        debug!(
+            "add_liveness_constraints: any_local_needs_drop={:?}",
+            any_local_needs_drop
+        );
+
+        for &bb in blocks {
+            self.sink.set_current_block(Some(bb));
+            self.add_block_liveness_constraints(bb, any_local_needs_drop);
         }
+        self.sink.set_current_block(None);
+    }
+
+    fn add_block_liveness_constraints(&mut self, bb: BasicBlock, any_local_needs_drop: bool) {
+        debug!("add_block_liveness_constraints(bb={:?})", bb);
+
+        // The liveness results treat `StorageLive` and `StorageDead` as
+        // definitions, so a local is never live outside of its storage
+        // range, and neither are the regions in its type.
+        //
+        // Note that generators need no special treatment: a local that
+        // is used after a `yield` is live at the `Yield` terminator
+        // (whose resume block is an ordinary successor), so the regions
+        // in its type include the suspension point.
+        //
+        // Nor does a `SetDiscriminant` through a reference (e.g., on
+        // `*r`): only the place itself is stored to, so the liveness
+        // results count the projection as a use of `r`, and the
+        // regions in its type include the statement. (In any case,
+        // these statements are only introduced by the deaggregator,
+        // after borrow checking.)
+
+        self.liveness
+            .regular
+            .simulate_block(self.mir, bb, |location, live_locals| {
+                for live_local in live_locals.iter() {
+                    let live_local_ty = self.mir.local_decls[live_local].ty;
+                    self.add_regular_live_constraint(live_local_ty, location);
+                }
+            });
+
+        // The arguments hold their values on entry to the function,
+        // whether or not they are used afterwards. No live points are
+        // needed for them: their types are renumbered with the free
+        // regions of the signature, which contain every point in the
+        // CFG, the entry included.
+
+        // The return place is not mentioned by the `Return` terminator,
+        // so the liveness results do not count it as live there. No
+        // live point is needed for it, though: the regions in its type
+        // are the free regions of the signature, which already include
+        // every point in the CFG.
+
+        if !any_local_needs_drop {
+            return;
+        }
+
+        self.liveness
+            .drop
+            .simulate_block(self.mir, bb, |location, live_locals| {
+                for live_local in live_locals.iter() {
+                    let live_local_ty = self.mir.local_decls[live_local].ty;
+                    self.add_drop_live_constraint(live_local_ty, location);
+                }
+            });
+    }
+
+    /// Returns true if dropping a value of type `ty` may run a
+    /// destructor or drop glue. The regions are erased first to get a
+    /// global type; whether a type needs drop does not depend on the
+    /// precise regions involved.
+    fn needs_drop(&self, ty: Ty<'tcx>) -> bool {
+        let tcx = self.infcx.tcx;
+        let gcx = tcx.global_tcx();
+        let erased_ty = gcx.lift(&tcx.erase_regions(&ty)).unwrap();
+        erased_ty.needs_drop(gcx, self.param_env)
     }
 
     /// Some variable with type `live_ty` is "regular live" at
@@ -98,11 +340,30 @@ impl<'cx, 'gcx, 'tcx> ConstraintGeneration<'cx, 'gcx, 'tcx> {
             location
         );
 
+        // Many live types (e.g., `i32` or `String`) contain no
+        // regions at all, in which case there is nothing to do. All
+        // regions in the MIR have been renumbered into inference
+        // variables by this point, so those are what we look for.
+        if !live_ty.has_type_flags(TypeFlags::HAS_RE_INFER) {
+            return;
+        }
+
+        // Note that this also covers `impl Trait` types: the regions
+        // an anonymous type may capture all appear in its substs,
+        // which `for_each_free_region` walks. Likewise, the region
+        // bound of a trait object (`'a` in `Box<Trait + 'a>`) is
+        // visited along with the rest of the type.
+
+        // A region may appear several times in `live_ty` (e.g., `'a` in
+        // `(&'a T, &'a U)`), in which case the same live point is
+        // added more than once. That is harmless: `add_live_point`
+        // ignores a point the region already contains, and it is
+        // cheaper than gathering the distinct regions first.
         self.infcx
             .tcx
             .for_each_free_region(&live_ty, |live_region| {
                 let vid = live_region.to_region_vid();
-                self.regioncx.add_live_point(vid, location);
+                self.sink.add_live_point(vid, location);
             });
     }
 
@@ -118,15 +379,41 @@ impl<'cx, 'gcx, 'tcx> ConstraintGeneration<'cx, 'gcx, 'tcx> {
             location
         );
 
-        let tcx = self.infcx.tcx;
-        let mut types = vec![(dropped_ty, 0)];
-        let mut known = FxHashSet();
+        // Types that have no destructor and no drop glue (e.g., `Copy`
+        // types) never touch any of their regions when dropped.
+        if !self.needs_drop(dropped_ty) {
+            debug!("add_drop_live_constraint: {:?} does not need drop", dropped_ty);
+            return;
+        }
+
+        if self.overflowed_drop_tys.contains(&dropped_ty) {
+            debug!("add_drop_live_constraint: {:?} overflowed before", dropped_ty);
+            return;
+        }
+
+        let span = self.mir.source_info(location).span;
+
+        // Reuse the scratch buffers of earlier calls; they are put
+        // back (empty, but with their capacity) once we are done.
+        let mut types = mem::replace(&mut self.drop_scratch.types, vec![]);
+        let mut known = mem::replace(&mut self.drop_scratch.known, FxHashSet());
+        let mut normalized = mem::replace(&mut self.drop_scratch.normalized, FxHashSet());
+        types.push((dropped_ty, 0));
+        known.insert(dropped_ty);
         while let Some((ty, depth)) = types.pop() {
-            let span = DUMMY_SP; // FIXME
-            let result = match tcx.dtorck_constraint_for_ty(span, dropped_ty, depth, ty) {
+            // Note that `dropped_ty` (rather than `ty`) is passed as the
+            // type "being dropped": it is only used to report overflow,
+            // which should name the type the user actually dropped.
+            let result = match self.dtorck_constraint_for_ty(span, dropped_ty, depth, ty) {
                 Ok(result) => result,
                 Err(ErrorReported) => {
-                    continue;
+                    // The overflow has been reported. The types still
+                    // to be processed are most likely part of the same
+                    // runaway expansion, so give up on `dropped_ty`
+                    // rather than report it again for each of them.
+                    self.overflowed_drop_tys.insert(dropped_ty);
+                    types.clear();
+                    break;
                 }
             };
 
@@ -152,7 +439,14 @@ impl<'cx, 'gcx, 'tcx> ConstraintGeneration<'cx, 'gcx, 'tcx> {
             // associated types and parameters). We need to normalize
             // associated types here and possibly recursively process.
             for ty in dtorck_types {
-                let cause = ObligationCause::dummy();
+                // The same type may be reached along several paths
+                // (e.g., `T` in both fields of `(Vec<T>, Vec<T>)`);
+                // there is no need to normalize it more than once.
+                if !normalized.insert(ty) {
+                    continue;
+                }
+
+                let cause = ObligationCause::misc(span, self.body_id.node_id);
                 // We know that our original `dropped_ty` is well-formed,
                 // so region obligations resulting from this normalization
                 // should always hold.
@@ -174,57 +468,248 @@ impl<'cx, 'gcx, 'tcx> ConstraintGeneration<'cx, 'gcx, 'tcx> {
                     },
 
                     Err(errors) => {
-                        self.infcx.report_fulfillment_errors(&errors, None);
+                        // If errors were already reported (e.g., by the
+                        // type checker), this failure is most likely a
+                        // consequence of them; reporting it would only
+                        // add noise.
+                        if !self.infcx.tcx.sess.has_errors() {
+                            self.infcx.report_fulfillment_errors(&errors, Some(self.body_id));
+                        }
                     }
                 }
             }
         }
+
+        known.clear();
+        normalized.clear();
+        self.drop_scratch = DropScratch {
+            types,
+            known,
+            normalized,
+        };
     }
 
-    fn add_borrow_constraints(&mut self) {
-        self.visit_mir(self.mir);
+    /// Computes the dtorck constraint for `ty`, reusing the result
+    /// of an earlier query for the same type if there was one. Errors
+    /// are not cached, so that they are reported with the span at
+    /// which they occur.
+    fn dtorck_constraint_for_ty(
+        &mut self,
+        span: Span,
+        dropped_ty: Ty<'tcx>,
+        depth: usize,
+        ty: Ty<'tcx>,
+    ) -> Result<ty::DtorckConstraint<'tcx>, ErrorReported> {
+        if let Some(result) = self.dtorck_cache.get(&ty) {
+            debug!("dtorck_constraint_for_ty: cache hit for {:?}", ty);
+            return Ok(result.clone());
+        }
+
+        let result = self.infcx
+            .tcx
+            .dtorck_constraint_for_ty(span, dropped_ty, depth, ty)?;
+        self.dtorck_cache.insert(ty, result.clone());
+        Ok(result)
+    }
+
+    /// Borrow constraints:
+    ///
+    /// > For each borrow `&'b L` in `blocks`, the references that `L`
+    /// > is reached through must outlive `'b`.
+    ///
+    /// The constraints added are tagged with the block they come from.
+    pub(crate) fn add_borrow_constraints(&mut self, blocks: &[BasicBlock]) {
+        let mir = self.mir;
+        for &bb in blocks {
+            self.sink.set_current_block(Some(bb));
+            self.visit_basic_block_data(bb, &mir[bb]);
+        }
+        self.sink.set_current_block(None);
     }
 
+    /// Walks the projections of `borrowed_place` from the outside in,
+    /// requiring each reference that the place is reached through to
+    /// outlive `borrow_region`. This is written as a loop, rather than
+    /// recursively, so that deeply nested places do not overflow the
+    /// stack.
     fn add_reborrow_constraint(
         &mut self,
         location: Location,
         borrow_region: ty::Region<'tcx>,
         borrowed_place: &Place<'tcx>,
     ) {
-        if let Projection(ref proj) = *borrowed_place {
+        debug!(
+            "add_reborrow_constraint(location={:?}, borrow_region={:?}, borrowed_place={:?})",
+            location,
+            borrow_region,
+            borrowed_place
+        );
+
+        // `-Z nll-disable-reborrow-constraints` lets users compare the
+        // errors reported with and without these constraints.
+        if self.infcx.tcx.sess.opts.debugging_opts.nll_disable_reborrow_constraints {
+            return;
+        }
+
+        let mut borrowed_place = borrowed_place;
+        while let Projection(ref proj) = *borrowed_place {
+            debug!("add_reborrow_constraint: borrowed_place={:?}", borrowed_place);
             let PlaceProjection { ref base, ref elem } = **proj;
 
-            if let ProjectionElem::Deref = *elem {
-                let tcx = self.infcx.tcx;
-                let base_ty = base.ty(self.mir, tcx).to_ty(tcx);
-                let base_sty = &base_ty.sty;
+            match *elem {
+                ProjectionElem::Deref => {
+                    let tcx = self.infcx.tcx;
+                    // The types of locals, and the field types recorded
+                    // in `Field` projections, are already normalized, so
+                    // e.g. a field declared as `<P as Trait>::Target`
+                    // shows up here as the reference it stands for.
+                    let base_ty = base.ty(self.mir, tcx).to_ty(tcx);
 
-                if let ty::TyRef(base_region, ty::TypeAndMut{ ty: _, mutbl }) = *base_sty {
-                    match mutbl {
-                        hir::Mutability::MutImmutable => { },
+                    match base_ty.sty {
+                        // An error has already been reported for this
+                        // path; there is nothing sensible to relate.
+                        ty::TyError => {
+                            debug!("add_reborrow_constraint: base {:?} has an error type", base);
+                            break;
+                        }
 
-                        hir::Mutability::MutMutable => {
-                            self.add_reborrow_constraint(location, borrow_region, base);
-                        },
-                    }
+                        ty::TyRef(base_region, ty::TypeAndMut { ty: referent_ty, mutbl }) => {
+                            // The reference being reborrowed must
+                            // outlive the new borrow, so `base_region`
+                            // is always the `sup`. This is the same for
+                            // shared and mutable references; they only
+                            // differ in whether we keep walking below.
+                            let span = self.mir.source_info(location).span;
+                            let base_vid = base_region.to_region_vid();
+                            let borrow_vid = borrow_region.to_region_vid();
+                            let interior_mutable =
+                                self.is_interior_mutable_ref(mutbl, referent_ty, span);
+                            let category = if interior_mutable {
+                                ConstraintCategory::InteriorMutableReborrow
+                            } else {
+                                ConstraintCategory::Reborrow
+                            };
+                            // Borrows (`Rvalue::Ref`) only appear in
+                            // assignment statements, never in terminators,
+                            // so there is always a point just after
+                            // `location` within the block -- at worst, the
+                            // terminator itself, from where the constraint
+                            // flows into each successor.
+                            let point = location.successor_within_block();
+                            self.sink.add_outlives(span, category, base_vid, borrow_vid, point);
+
+                            // Log each decision under its own target, so
+                            // that it can be enabled with
+                            // `RUST_LOG=nll::reborrow`. This is logged at
+                            // the `info` level so that it is available in
+                            // release builds of the compiler too.
+                            info!(
+                                target: "nll::reborrow",
+                                "place={:?} base_ty={:?} mutability={:?} \
+                                 outlives_edge=({:?}: {:?})",
+                                borrowed_place,
+                                base_ty,
+                                mutbl,
+                                base_vid,
+                                borrow_vid
+                            );
+
+                            // The referent of a shared reference is frozen
+                            // for as long as `base_region` is live, so the
+                            // paths it was reached through impose no
+                            // further constraints. With `-Z
+                            // nll-conservative-reborrows`, shared references
+                            // are walked through as if they were mutable
+                            // instead; this only adds constraints.
+                            let conservative = self.infcx
+                                .tcx
+                                .sess
+                                .opts
+                                .debugging_opts
+                                .nll_conservative_reborrows;
+                            if mutbl == hir::MutImmutable && !conservative {
+                                break;
+                            }
+                        }
+
+                        // A `Box` has no region of its own, but it uniquely
+                        // owns its referent, so the box itself may have been
+                        // reached through a reference (e.g., `&mut **x`
+                        // where `x: &mut Box<T>`). Keep walking.
+                        _ if base_ty.is_box() => { }
 
-                    let span = self.mir.source_info(location).span;
-                    self.regioncx.add_outlives(span,
-                                               base_region.to_region_vid(),
-                                               borrow_region.to_region_vid(),
-                                               location.successor_within_block());
+                        // A raw pointer carries no region, and nothing
+                        // ties the lifetime of its referent to the paths
+                        // the pointer was reached through, so there are
+                        // no further constraints.
+                        ty::TyRawPtr(..) => break,
+
+                        // Other smart pointers (e.g., `Rc<T>`) never show
+                        // up as the base of a `Deref` projection: MIR
+                        // construction lowers an overloaded deref into a
+                        // call to `Deref::deref`, whose signature relates
+                        // the region of the reference to the smart pointer
+                        // and the region of the result. The place borrowed
+                        // here then starts from that result.
+                        _ => break,
+                    }
                 }
+
+                // Selecting a field (possibly of an enum variant) or
+                // indexing into an array or slice does not introduce a
+                // region of its own, but the value being projected from
+                // may itself be reached through a reference (e.g.,
+                // `&mut (*x).f` or `&mut (*x)[i]`), so keep walking
+                // towards the base. This holds for arrays as well as
+                // slices, and for constant indices counted from either
+                // end of a slice.
+                ProjectionElem::Field(..) |
+                ProjectionElem::Downcast(..) |
+                ProjectionElem::Index(_) |
+                ProjectionElem::ConstantIndex { .. } |
+                ProjectionElem::Subslice { .. } => { }
             }
+
+            borrowed_place = base;
         }
     }
+
+    /// Returns true if a reference with mutability `mutbl` and
+    /// referent `referent_ty` is a shared reference to something with
+    /// interior mutability (i.e., an `UnsafeCell`, or a type such as
+    /// `Cell` that contains one). As in `needs_drop`, the regions are
+    /// erased first; they do not affect whether a type is `Freeze`.
+    fn is_interior_mutable_ref(
+        &self,
+        mutbl: hir::Mutability,
+        referent_ty: Ty<'tcx>,
+        span: Span,
+    ) -> bool {
+        if mutbl != hir::MutImmutable {
+            return false;
+        }
+
+        let tcx = self.infcx.tcx;
+        let gcx = tcx.global_tcx();
+        let erased_ty = gcx.lift(&tcx.erase_regions(&referent_ty)).unwrap();
+        !erased_ty.is_freeze(gcx, self.param_env, span)
+    }
 }
 
-impl<'cx, 'gcx, 'tcx> Visitor<'tcx> for ConstraintGeneration<'cx, 'gcx, 'tcx> {
+impl<'cx, 'gcx, 'tcx, S> Visitor<'tcx> for ConstraintGeneration<'cx, 'gcx, 'tcx, S>
+where
+    S: ConstraintSink + 'cx,
+{
     fn visit_rvalue(&mut self,
                     rvalue: &Rvalue<'tcx>,
                     location: Location) {
         debug!("visit_rvalue(rvalue={:?}, location={:?})", rvalue, location);
 
+        if cfg!(debug_assertions) {
+            let first_visit = self.visited_rvalues.insert(location);
+            assert!(first_visit, "visit_rvalue: rvalue at {:?} visited more than once", location);
+        }
+
         // Look for an rvalue like:
         //
         //     & L
@@ -232,10 +717,89 @@ impl<'cx, 'gcx, 'tcx> Visitor<'tcx> for ConstraintGeneration<'cx, 'gcx, 'tcx> {
         // where L is the path that is borrowed. In that case, we have
         // to add the reborrow constraints (which don't fall out
         // naturally from the type-checker).
+        //
+        // Other rvalues that read through a reference, such as
+        // `Discriminant(*r)`, need no special treatment: they count as
+        // a regular use of `r`, and so the liveness constraints already
+        // require the regions in the type of `r` to include `location`.
+        //
+        // Aggregates (e.g., `(&mut a, &mut b)`) need no special
+        // treatment: each reference is first stored into a temporary
+        // by its own `Rvalue::Ref`, and the type-checker relates the
+        // types of the operands to the type of the aggregate.
+        //
+        // Closures need no special treatment either: when a closure
+        // captures an upvar by reference, MIR construction emits an
+        // explicit `&x` (or `&mut x`, or `&uniq x`) for it before
+        // building the `Rvalue::Aggregate`, and that borrow is handled
+        // here like any other. The temporary holding the borrow is an
+        // operand of the aggregate, so its region is live at the point
+        // where the closure is created; from then on, the region
+        // appears in the closure's type and is live wherever the
+        // closure is.
+        //
+        // Every `BorrowKind` is activated where it is created (there
+        // are no two-phase borrows yet), so the reborrow constraints
+        // always start from `location`.
         if let Rvalue::Ref(region, _bk, ref borrowed_place) = *rvalue {
+            if let Some(ref mut all_facts) = *self.all_facts {
+                all_facts
+                    .borrow_region
+                    .push((region.to_region_vid(), location));
+            }
+
             self.add_reborrow_constraint(location, region, borrowed_place);
         }
 
+        // Casting a reference to a raw pointer (e.g., `&mut x as *mut
+        // T`) needs no special treatment: the operand is an ordinary
+        // use of the reference, so the liveness constraints already
+        // require its region to include the cast, and the resulting
+        // pointer carries no region.
+        //
+        // There is no separate rvalue for creating a raw pointer
+        // directly from a place: it is always an ordinary borrow,
+        // handled above, followed by such a cast.
+
         self.super_rvalue(rvalue, location);
     }
+
+    fn visit_terminator(&mut self,
+                        block: BasicBlock,
+                        terminator: &Terminator<'tcx>,
+                        location: Location) {
+        debug!("visit_terminator(terminator={:?}, location={:?})", terminator, location);
+
+        // A `SwitchInt` may test a place like `*r` directly, rather
+        // than a temporary holding its value. This needs no special
+        // treatment: the liveness results count the projection as a
+        // use of `r`, so the regions in its type include the switch
+        // itself, even if `r` is not used again in any branch.
+        //
+        // The value of a `Yield` is an ordinary operand, so the
+        // liveness constraints already cover it (and `visit_constant`
+        // covers a constant one). Generators do not take a resume
+        // argument, so there is no destination to constrain.
+        //
+        // Likewise, a `Call` needs no special treatment: the MIR type
+        // checker relates the return type of the callee to the type
+        // of the destination place, and from the successor onwards
+        // the destination is an ordinary live local.
+        self.super_terminator(block, terminator, location);
+    }
+
+    fn visit_constant(&mut self,
+                      constant: &Constant<'tcx>,
+                      location: Location) {
+        debug!("visit_constant(constant={:?}, location={:?})", constant, location);
+
+        // Constants (including promoted references like `&22`) do not
+        // appear in `local_decls`, so the liveness constraints do not
+        // cover them. But the constant is used at `location`, so the
+        // regions in its type must include that point, just as for
+        // a live local.
+        self.add_regular_live_constraint(constant.ty, location);
+
+        self.super_constant(constant, location);
+    }
 }
@@ -0,0 +1,61 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use rustc::mir::Location;
+use rustc::ty::RegionVid;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// The raw facts used by NLL, in the form of relations suitable for
+/// consumption by external tools (e.g., the Polonius formulation of
+/// the borrow checker). These are dumped when `-Z nll-facts` is given.
+#[derive(Default)]
+pub struct AllFacts {
+    /// `borrow_region(R, P)` -- a borrow with region `R` occurs at `P`.
+    pub borrow_region: Vec<(RegionVid, Location)>,
+
+    /// `outlives(R1, R2, P)` -- `R1` must outlive `R2` at `P`.
+    pub outlives: Vec<(RegionVid, RegionVid, Location)>,
+
+    /// `region_live_at(R, P)` -- the region `R` is live at `P`.
+    pub region_live_at: Vec<(RegionVid, Location)>,
+}
+
+impl AllFacts {
+    /// Writes each relation into a file `<relation>.csv` within
+    /// `dir`, one row per fact.
+    pub fn write_to_dir(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+
+        write_rows(&dir.join("borrow_region.csv"), &self.borrow_region, |&(r, p)| {
+            format!("{:?},{:?}", r, p)
+        })?;
+        write_rows(&dir.join("outlives.csv"), &self.outlives, |&(r1, r2, p)| {
+            format!("{:?},{:?},{:?}", r1, r2, p)
+        })?;
+        write_rows(&dir.join("region_live_at.csv"), &self.region_live_at, |&(r, p)| {
+            format!("{:?},{:?}", r, p)
+        })?;
+
+        Ok(())
+    }
+}
+
+fn write_rows<T, F>(path: &Path, rows: &[T], format_row: F) -> io::Result<()>
+where
+    F: Fn(&T) -> String,
+{
+    let mut file = File::create(path)?;
+    for row in rows {
+        writeln!(file, "{}", format_row(row))?;
+    }
+    Ok(())
+}
@@ -9,24 +9,35 @@
 // except according to those terms.
 
 use rustc::hir::def_id::DefId;
-use rustc::mir::Mir;
+use rustc::mir::{BasicBlock, Local, Location, Mir};
 use rustc::infer::InferCtxt;
 use rustc::ty::{self, RegionKind, RegionVid};
-use rustc::util::nodemap::FxHashMap;
+use rustc::util::nodemap::{FxHashMap, FxHashSet};
+use rustc_data_structures::indexed_vec::IndexVec;
 use std::collections::BTreeSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use syntax_pos::Span;
+use self::facts::AllFacts;
 use transform::MirSource;
-use transform::type_check;
+use transform::type_check::{self, MirTypeckRegionConstraints};
 use util::liveness::{self, LivenessMode, LivenessResult, LocalSet};
 
 use util as mir_util;
 use self::mir_util::PassWhere;
 
 mod constraint_generation;
+pub use self::constraint_generation::ConstraintSink;
+pub(crate) use self::constraint_generation::ConstraintGeneration;
+mod facts;
 mod subtype_constraint_generation;
 mod free_regions;
 
 pub(crate) mod region_infer;
+pub use self::region_infer::ConstraintCategory;
 use self::region_infer::RegionInferenceContext;
+use self::free_regions::FreeRegions;
 
 mod renumber;
 
@@ -53,45 +64,381 @@ pub fn compute_regions<'a, 'gcx, 'tcx>(
     // data that was contained in `infcx`.
     let var_origins = infcx.take_region_var_origins();
     let mut regioncx = RegionInferenceContext::new(var_origins, free_regions, mir);
+    let debugging_opts = &infcx.tcx.sess.opts.debugging_opts;
+    if debugging_opts.dump_nll_summary {
+        regioncx.record_point_blame();
+    }
+    if debugging_opts.nll_verify_recompute || debugging_opts.nll_warn_unconstrained ||
+        debugging_opts.nll_report_hot_regions.is_some() || debugging_opts.dump_nll_summary ||
+        debugging_opts.nll_verify_stream {
+        regioncx.keep_live_points();
+    }
     subtype_constraint_generation::generate(&mut regioncx, free_regions, mir, constraint_sets);
 
     // Compute what is live where.
-    let liveness = &LivenessResults {
-        regular: liveness::liveness_of_locals(
-            &mir,
-            LivenessMode {
-                include_regular_use: true,
-                include_drops: false,
-            },
-        ),
+    let liveness = &LivenessResults::compute(mir);
 
-        drop: liveness::liveness_of_locals(
-            &mir,
-            LivenessMode {
-                include_regular_use: false,
-                include_drops: true,
-            },
-        ),
+    let mut all_facts = if infcx.tcx.sess.opts.debugging_opts.nll_facts {
+        Some(AllFacts::default())
+    } else {
+        None
     };
 
     // Generate non-subtyping constraints.
-    constraint_generation::generate_constraints(infcx, &mut regioncx, &mir, param_env, liveness);
+    if cfg!(debug_assertions) {
+        regioncx.debug_assert_mir_regions_exist(infcx, &mir);
+    }
+    let body_id = infcx.tcx.hir.body_owned_by(mir_node_id);
+    let stream_mark = if debugging_opts.nll_verify_stream {
+        Some(regioncx.constraints_mark())
+    } else {
+        None
+    };
+    constraint_generation::generate_constraints(
+        infcx,
+        &mut regioncx,
+        &mir,
+        body_id,
+        param_env,
+        liveness,
+        &[],
+        &mut all_facts,
+    );
+
+    // Check that streaming the constraints gives the same ones as
+    // were just generated, if that is enabled.
+    if let Some(mark) = stream_mark {
+        verify_stream(infcx, def_id, param_env, mir, &regioncx, mark);
+    }
+
+    // Warn about lifetime parameters that nothing constrains, if that
+    // is enabled.
+    if infcx.tcx.sess.opts.debugging_opts.nll_warn_unconstrained {
+        for (_, name) in regioncx.unconstrained_named_regions() {
+            infcx.tcx.sess.span_warn(
+                mir.span,
+                &format!("lifetime `{}` is not constrained by the body of this function", name),
+            );
+        }
+    }
+
+    // Warn about regions that are live at many points, if that is
+    // enabled.
+    if let Some(threshold) = infcx.tcx.sess.opts.debugging_opts.nll_report_hot_regions {
+        for (region, count) in regioncx.hot_regions(threshold) {
+            infcx.tcx.sess.span_warn(
+                mir.span,
+                &format!("region `{:?}` is live at {} points", region, count),
+            );
+        }
+    }
+
+    // Dump the NLL facts, if that is enabled.
+    if let Some(mut all_facts) = all_facts {
+        regioncx.add_facts(&mut all_facts, dump_filter(infcx));
+        let def_path = infcx.tcx.hir.def_path(def_id);
+        let dir_path = PathBuf::from("nll-facts").join(def_path.to_filename_friendly_no_crate());
+        if let Err(e) = all_facts.write_to_dir(&dir_path) {
+            infcx.tcx.sess.warn(&format!("failed to write NLL facts: {}", e));
+        }
+    }
 
     // Solve the region constraints.
+    let err_count = infcx.tcx.sess.err_count();
     regioncx.solve(infcx, &mir);
 
+    // Record how long it took to solve, for `-Z nll-stats`.
+    let perf_stats = &infcx.tcx.sess.perf_stats;
+    let iterations = regioncx.propagation_iterations() as u64;
+    perf_stats.nll_bodies_count.set(perf_stats.nll_bodies_count.get() + 1);
+    perf_stats
+        .nll_propagation_iterations
+        .set(perf_stats.nll_propagation_iterations.get() + iterations);
+    if iterations > perf_stats.nll_max_propagation_iterations.get() {
+        perf_stats.nll_max_propagation_iterations.set(iterations);
+    }
+
+    // Check the solution against the outlives constraints, if that is enabled.
+    if debugging_opts.verify_nll_regions {
+        regioncx.verify_constraints(mir);
+    }
+
+    // Check that recomputing the regions block by block gives the
+    // same solution, if that is enabled. If errors were reported, they
+    // would be reported again, so this is skipped.
+    if debugging_opts.nll_verify_recompute && infcx.tcx.sess.err_count() == err_count {
+        verify_recompute(
+            infcx,
+            def_id,
+            param_env,
+            free_regions,
+            mir,
+            constraint_sets,
+            &mut regioncx,
+        );
+    }
+
     // Dump MIR results into a file, if that is enabled. This let us
     // write unit-tests.
     dump_mir_results(infcx, liveness, MirSource::item(def_id), &mir, &regioncx);
 
+    // Dump the region constraint graph, if that is enabled.
+    dump_constraint_graph(infcx, def_id, &regioncx);
+
+    // Dump a summary of each region, if that is enabled.
+    dump_summary(infcx, def_id, &regioncx);
+
+    // Dump the final value of each region, if that is enabled.
+    dump_region_values(infcx, def_id, &regioncx);
+
+    // Dump the live points of each local, if that is enabled.
+    dump_liveness(infcx, def_id, &mir, liveness);
+
     regioncx
 }
 
-struct LivenessResults {
+/// Updates the results of `compute_regions` after the basic blocks in
+/// `dirty_blocks` have been modified. The constraints that were
+/// generated from those blocks (of every kind, including those from
+/// the type-checker's `constraint_sets`) are removed, regenerated from
+/// the current MIR, and the regions are solved again; the constraints
+/// of all other blocks are reused. The live points of `regioncx` must
+/// have been kept (see `keep_live_points`).
+///
+/// Liveness is recomputed for the whole body, but only applied to the
+/// dirty blocks, so the caller must include every block whose
+/// liveness may have changed as a result of the edit.
+fn recompute_regions<'a, 'gcx, 'tcx>(
+    infcx: &InferCtxt<'a, 'gcx, 'tcx>,
+    def_id: DefId,
+    param_env: ty::ParamEnv<'gcx>,
+    free_regions: &FreeRegions<'tcx>,
+    mir: &Mir<'tcx>,
+    constraint_sets: &MirTypeckRegionConstraints<'tcx>,
+    regioncx: &mut RegionInferenceContext<'tcx>,
+    dirty_blocks: &[BasicBlock],
+) {
+    let dirty_set: FxHashSet<_> = dirty_blocks.iter().cloned().collect();
+    regioncx.retract_blocks(&dirty_set);
+
+    subtype_constraint_generation::regenerate(
+        regioncx,
+        free_regions,
+        mir,
+        constraint_sets,
+        &dirty_set,
+    );
+
+    let liveness = &LivenessResults::compute(mir);
+    let mir_node_id = infcx.tcx.hir.as_local_node_id(def_id).unwrap();
+    let body_id = infcx.tcx.hir.body_owned_by(mir_node_id);
+    constraint_generation::regenerate_constraints(
+        infcx,
+        regioncx,
+        mir,
+        body_id,
+        param_env,
+        liveness,
+        dirty_blocks,
+    );
+
+    regioncx.solve(infcx, mir);
+    if infcx.tcx.sess.opts.debugging_opts.verify_nll_regions {
+        regioncx.verify_constraints(mir);
+    }
+}
+
+/// Checks, for `-Z nll-verify-recompute`, that recomputing the regions
+/// of each basic block in turn with `recompute_regions` leaves both
+/// the number of constraints and the solved region values unchanged.
+fn verify_recompute<'a, 'gcx, 'tcx>(
+    infcx: &InferCtxt<'a, 'gcx, 'tcx>,
+    def_id: DefId,
+    param_env: ty::ParamEnv<'gcx>,
+    free_regions: &FreeRegions<'tcx>,
+    mir: &Mir<'tcx>,
+    constraint_sets: &MirTypeckRegionConstraints<'tcx>,
+    regioncx: &mut RegionInferenceContext<'tcx>,
+) {
+    let constraints_len = regioncx.constraints_len();
+    let region_values: Vec<_> = regioncx
+        .regions()
+        .map(|region| regioncx.region_value_str(region))
+        .collect();
+
+    for bb in mir.basic_blocks().indices() {
+        recompute_regions(
+            infcx,
+            def_id,
+            param_env,
+            free_regions,
+            mir,
+            constraint_sets,
+            regioncx,
+            &[bb],
+        );
+
+        if regioncx.constraints_len() != constraints_len {
+            bug!(
+                "recomputing {:?} of {:?} gave {} constraints instead of {}",
+                bb,
+                def_id,
+                regioncx.constraints_len(),
+                constraints_len
+            );
+        }
+        for (region, value) in regioncx.regions().zip(&region_values) {
+            let new_value = regioncx.region_value_str(region);
+            if new_value != *value {
+                bug!(
+                    "recomputing {:?} of {:?} changed the value of {:?} from {} to {}",
+                    bb,
+                    def_id,
+                    region,
+                    value,
+                    new_value
+                );
+            }
+        }
+    }
+}
+
+/// Checks, for `-Z nll-verify-stream`, that `stream_constraints`
+/// gives the same live points and outlives constraints as were added
+/// to `regioncx` by constraint generation since `mark`, and that
+/// driving only the borrow phase of a `ConstraintGeneration` gives the
+/// same outlives constraints.
+fn verify_stream<'a, 'gcx, 'tcx>(
+    infcx: &InferCtxt<'a, 'gcx, 'tcx>,
+    def_id: DefId,
+    param_env: ty::ParamEnv<'gcx>,
+    mir: &Mir<'tcx>,
+    regioncx: &RegionInferenceContext<'tcx>,
+    mark: (usize, usize),
+) {
+    let mut collected = CollectedConstraints::default();
+    stream_constraints(infcx, def_id, param_env, mir, &mut collected);
+    regioncx.verify_streamed_constraints(mark, &collected.live_points, &collected.outlives);
+
+    // Only the borrow phase adds outlives constraints, so running it
+    // on its own must give all of them. It does not consult liveness,
+    // so no local needs to be live anywhere.
+    let liveness = &LivenessResults::empty(mir);
+    let mir_node_id = infcx.tcx.hir.as_local_node_id(def_id).unwrap();
+    let body_id = infcx.tcx.hir.body_owned_by(mir_node_id);
+    let blocks: Vec<_> = mir.basic_blocks().indices().collect();
+    let mut borrows_only = CollectedConstraints::default();
+    ConstraintGeneration::new(
+        infcx,
+        &mut borrows_only,
+        mir,
+        body_id,
+        param_env,
+        liveness,
+        &[],
+        &mut None,
+    ).add_borrow_constraints(&blocks);
+    if borrows_only.outlives != collected.outlives {
+        bug!(
+            "the borrow phase of {:?} gave {} outlives constraints instead of {}",
+            def_id,
+            borrows_only.outlives.len(),
+            collected.outlives.len()
+        );
+    }
+}
+
+/// A `ConstraintSink` that only collects the distinct live points and
+/// outlives constraints it is given. This is used by `verify_stream`.
+#[derive(Default)]
+struct CollectedConstraints {
+    live_points: FxHashSet<(RegionVid, Location)>,
+    outlives: FxHashSet<(RegionVid, RegionVid, Location)>,
+}
+
+impl ConstraintSink for CollectedConstraints {
+    fn add_live_point(&mut self, v: RegionVid, point: Location) {
+        self.live_points.insert((v, point));
+    }
+
+    fn add_outlives(
+        &mut self,
+        _span: Span,
+        _category: ConstraintCategory,
+        sup: RegionVid,
+        sub: RegionVid,
+        point: Location,
+    ) {
+        self.outlives.insert((sup, sub, point));
+    }
+}
+
+/// Generates the liveness and outlives constraints of `mir` (which
+/// must already have been renumbered and type-checked, as by
+/// `compute_regions`) into `sink`, rather than into a region inference
+/// context. This lets a caller process the constraints as they are
+/// produced instead of storing them all.
+pub fn stream_constraints<'a, 'gcx, 'tcx, S: ConstraintSink>(
+    infcx: &InferCtxt<'a, 'gcx, 'tcx>,
+    def_id: DefId,
+    param_env: ty::ParamEnv<'gcx>,
+    mir: &Mir<'tcx>,
+    sink: &mut S,
+) {
+    let liveness = &LivenessResults::compute(mir);
+    let mir_node_id = infcx.tcx.hir.as_local_node_id(def_id).unwrap();
+    let body_id = infcx.tcx.hir.body_owned_by(mir_node_id);
+    constraint_generation::generate_constraints(
+        infcx,
+        sink,
+        mir,
+        body_id,
+        param_env,
+        liveness,
+        &[],
+        &mut None,
+    );
+}
+
+pub struct LivenessResults {
     regular: LivenessResult,
     drop: LivenessResult,
 }
 
+impl LivenessResults {
+    /// Computes the regular and drop liveness of each local in `mir`.
+    pub fn compute(mir: &Mir) -> Self {
+        LivenessResults {
+            regular: liveness::liveness_of_locals(
+                mir,
+                LivenessMode {
+                    include_regular_use: true,
+                    include_drops: false,
+                },
+            ),
+
+            drop: liveness::liveness_of_locals(
+                mir,
+                LivenessMode {
+                    include_regular_use: false,
+                    include_drops: true,
+                },
+            ),
+        }
+    }
+
+    /// Returns liveness results in which no local is live anywhere
+    /// in `mir`. This is useful for exercising the phases of
+    /// constraint generation that do not depend on liveness, as
+    /// `-Z nll-verify-stream` does with the borrow phase.
+    pub fn empty(mir: &Mir) -> Self {
+        LivenessResults {
+            regular: LivenessResult::empty(mir),
+            drop: LivenessResult::empty(mir),
+        }
+    }
+}
+
 fn dump_mir_results<'a, 'gcx, 'tcx>(
     infcx: &InferCtxt<'a, 'gcx, 'tcx>,
     liveness: &LivenessResults,
@@ -151,7 +498,149 @@ fn dump_mir_results<'a, 'gcx, 'tcx>(
                 writeln!(out, "            | Live variables at {:?}: {}", location, s)?;
             }
 
-            PassWhere::AfterCFG => {}
+            // After the CFG, dump out the outlives constraints.
+            PassWhere::AfterCFG => for (sup, sub, point, _) in regioncx.outlives_edges() {
+                writeln!(out, "| {:?}: {:?} @ {:?}", sup, sub, point)?;
+            },
+        }
+        Ok(())
+    });
+}
+
+fn dump_constraint_graph<'a, 'gcx, 'tcx>(
+    infcx: &InferCtxt<'a, 'gcx, 'tcx>,
+    def_id: DefId,
+    regioncx: &RegionInferenceContext,
+) {
+    let tcx = infcx.tcx;
+    if !tcx.sess.opts.debugging_opts.dump_nll_constraint_graph {
+        return;
+    }
+
+    let graph_name = format!("RegionConstraints_{}", tcx.hir.as_local_node_id(def_id).unwrap());
+    dump_to_file(infcx, def_id, "nll-constraints.dot", |file| {
+        regioncx.write_graphviz(&graph_name, dump_filter(infcx), file)
+    });
+}
+
+fn dump_summary<'a, 'gcx, 'tcx>(
+    infcx: &InferCtxt<'a, 'gcx, 'tcx>,
+    def_id: DefId,
+    regioncx: &RegionInferenceContext,
+) {
+    let tcx = infcx.tcx;
+    if !tcx.sess.opts.debugging_opts.dump_nll_summary {
+        return;
+    }
+
+    dump_to_file(infcx, def_id, "nll-summary.txt", |file| {
+        regioncx.write_summary(tcx.sess.codemap(), file)
+    });
+}
+
+fn dump_region_values<'a, 'gcx, 'tcx>(
+    infcx: &InferCtxt<'a, 'gcx, 'tcx>,
+    def_id: DefId,
+    regioncx: &RegionInferenceContext,
+) {
+    let tcx = infcx.tcx;
+    if !tcx.sess.opts.debugging_opts.dump_region_values {
+        return;
+    }
+
+    dump_to_file(infcx, def_id, "nll-region-values.txt", |file| {
+        regioncx.write_region_values(file)
+    });
+}
+
+/// Writes the file `rustc.<item>.<suffix>` for the item `def_id` into
+/// the `-Z dump-mir-dir` directory, with `write` producing its
+/// contents. This is shared by the NLL dumps enabled by the various
+/// `-Z` flags; the callers check their own flag first. A failure to
+/// write the file is reported as a warning, since the dumps are only
+/// a debugging aid.
+fn dump_to_file<'a, 'gcx, 'tcx, F>(
+    infcx: &InferCtxt<'a, 'gcx, 'tcx>,
+    def_id: DefId,
+    suffix: &str,
+    write: F,
+) where
+    F: FnOnce(&mut fs::File) -> io::Result<()>,
+{
+    let tcx = infcx.tcx;
+    let mut dir_path = PathBuf::new();
+    if let Some(ref file_dir) = tcx.sess.opts.debugging_opts.dump_mir_dir {
+        dir_path.push(file_dir);
+    }
+
+    let item_name = tcx.hir.def_path(def_id).to_filename_friendly_no_crate();
+    let file_path = dir_path.join(format!("rustc.{}.{}", item_name, suffix));
+
+    let result = fs::create_dir_all(&dir_path)
+        .and_then(|()| fs::File::create(&file_path))
+        .and_then(|mut file| write(&mut file));
+    if let Err(e) = result {
+        tcx.sess.warn(&format!("failed to write `{}`: {}", file_path.display(), e));
+    }
+}
+
+/// Returns the constraint category given by `-Z nll-dump-filter`, if
+/// any. Only constraints of this category are included when dumping
+/// the constraint graph or the NLL facts.
+fn dump_filter<'a, 'gcx, 'tcx>(infcx: &InferCtxt<'a, 'gcx, 'tcx>) -> Option<ConstraintCategory> {
+    let filter = match infcx.tcx.sess.opts.debugging_opts.nll_dump_filter {
+        Some(ref filter) => filter,
+        None => return None,
+    };
+
+    match &filter[..] {
+        "subtype" => Some(ConstraintCategory::Subtype),
+        "reborrow" => Some(ConstraintCategory::Reborrow),
+        "interior-mutable-reborrow" => Some(ConstraintCategory::InteriorMutableReborrow),
+        "seed" => Some(ConstraintCategory::Seed),
+        _ => {
+            infcx.tcx.sess.err(&format!(
+                "unknown constraint category `{}` given to `-Z nll-dump-filter` \
+                 (expected `subtype`, `reborrow`, `interior-mutable-reborrow` or `seed`)",
+                filter
+            ));
+            None
+        }
+    }
+}
+
+fn dump_liveness<'a, 'gcx, 'tcx>(
+    infcx: &InferCtxt<'a, 'gcx, 'tcx>,
+    def_id: DefId,
+    mir: &Mir<'tcx>,
+    liveness: &LivenessResults,
+) {
+    let tcx = infcx.tcx;
+    if !tcx.sess.opts.debugging_opts.dump_liveness {
+        return;
+    }
+
+    // Collect the points at which each local is (regular) live.
+    let mut live_points: IndexVec<Local, BTreeSet<Location>> =
+        mir.local_decls.iter().map(|_| BTreeSet::new()).collect();
+    for bb in mir.basic_blocks().indices() {
+        liveness
+            .regular
+            .simulate_block(mir, bb, |location, live_locals| {
+                for live_local in live_locals.iter() {
+                    live_points[live_local].insert(location);
+                }
+            });
+    }
+
+    dump_to_file(infcx, def_id, "nll-liveness.txt", |file| {
+        for (local, points) in live_points.iter_enumerated() {
+            if points.is_empty() {
+                writeln!(file, "{:?}: never live", local)?;
+            } else {
+                let runs = region_infer::point_runs_strs(points);
+                writeln!(file, "{:?}: {}", local, runs.join(", "))?;
+            }
         }
         Ok(())
     });
@@ -168,14 +657,30 @@ pub trait ToRegionVid {
 
 impl ToRegionVid for RegionKind {
     fn to_region_vid(&self) -> RegionVid {
-        if let &ty::ReVar(vid) = self {
-            vid
-        } else {
-            bug!("region is not an ReVar: {:?}", self)
+        match *self {
+            ty::ReVar(vid) => vid,
+
+            // Renumbering replaces every region in the MIR, including
+            // `'static` and erased regions, with an inference
+            // variable; seeing one of these means that some type was
+            // not renumbered (or was computed from the unrenumbered
+            // MIR).
+            ty::ReStatic | ty::ReErased => bug!(
+                "region {:?} was not renumbered; expected an inference variable",
+                self
+            ),
+
+            _ => bug!("region is not an ReVar: {:?}", self),
         }
     }
 }
 
+impl ToRegionVid for RegionVid {
+    fn to_region_vid(&self) -> RegionVid {
+        *self
+    }
+}
+
 fn live_variable_set(regular: &LocalSet, drops: &LocalSet) -> String {
     // sort and deduplicate:
     let all_locals: BTreeSet<_> = regular.iter().chain(drops.iter()).collect();
@@ -8,18 +8,22 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::facts::AllFacts;
 use super::free_regions::FreeRegions;
 use rustc::infer::InferCtxt;
 use rustc::infer::RegionVariableOrigin;
 use rustc::infer::NLLRegionVariableOrigin;
 use rustc::infer::region_constraints::VarOrigins;
-use rustc::mir::{Location, Mir};
+use rustc::mir::{BasicBlock, Location, Mir};
+use rustc::mir::visit::TyContext;
 use rustc::ty::{self, RegionVid};
-use rustc_data_structures::indexed_vec::IndexVec;
-use rustc_data_structures::fx::FxHashSet;
-use std::collections::BTreeSet;
+use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use std::collections::{BTreeSet, VecDeque};
 use std::fmt;
-use syntax_pos::Span;
+use std::io::{self, Write};
+use syntax::codemap::CodeMap;
+use syntax_pos::{Span, DUMMY_SP};
 
 pub struct RegionInferenceContext<'tcx> {
     /// Contains the definition for every region variable.  Region
@@ -30,6 +34,39 @@ pub struct RegionInferenceContext<'tcx> {
 
     /// The constraints we have accumulated and used during solving.
     constraints: Vec<Constraint>,
+
+    /// Maps the `(sup, sub, point)` triple of each constraint in
+    /// `constraints` to its index, used to avoid pushing the same
+    /// outlives relation more than once.
+    constraint_keys: FxHashMap<(RegionVid, RegionVid, Location), usize>,
+
+    /// If `record_point_blame` has been called, then for each point
+    /// that was added to a region's value during propagation, the
+    /// span of the constraint that first added it. Points that are in
+    /// the value because the region is live there (or because it is a
+    /// free region) have no entry.
+    point_blame: Option<FxHashMap<(RegionVid, Location), Span>>,
+
+    /// The number of rounds that `solve()` needed before the region
+    /// values stopped changing.
+    propagation_iterations: usize,
+
+    /// If `keep_live_points` has been called, each `(region, point)`
+    /// pair given to `add_live_point`, along with the basic block whose
+    /// constraints were being generated at the time (see
+    /// `set_current_block`). This lets `retract_blocks` rebuild the
+    /// region values without a block's contributions.
+    live_points: Option<Vec<(RegionVid, Location, Option<BasicBlock>)>>,
+
+    /// If constraints are currently being generated for a single
+    /// basic block, that block. Live points and constraints added in
+    /// the meantime are tagged with it.
+    current_block: Option<BasicBlock>,
+
+    /// The number of distinct `(region, point)` pairs that have been
+    /// added to the values of non-constant regions by
+    /// `add_live_point`.
+    live_points_count: usize,
 }
 
 struct RegionDefinition<'tcx> {
@@ -87,6 +124,21 @@ impl Region {
     fn contains_point(&self, point: Location) -> bool {
         self.points.contains(&point)
     }
+
+    fn contains_element(&self, element: RegionElement) -> bool {
+        match element {
+            RegionElement::Location(point) => self.points.contains(&point),
+            RegionElement::FreeRegion(fr) => self.free_regions.contains(&fr),
+        }
+    }
+}
+
+/// A single element of a region value: either a point in the CFG or
+/// the end of a free region.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum RegionElement {
+    Location(Location),
+    FreeRegion(RegionVid),
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -105,10 +157,40 @@ pub struct Constraint {
     /// At this location.
     point: Location,
 
+    /// Why was this constraint added?
+    category: ConstraintCategory,
+
+    /// The basic block whose constraint generation added this
+    /// constraint, if it was added on behalf of a single block.
+    block: Option<BasicBlock>,
+
     /// Where did this constraint arise?
     span: Span,
 }
 
+/// Records why an outlives constraint was added, so that diagnostics
+/// can explain which part of the program gave rise to it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConstraintCategory {
+    /// The constraint arose from subtyping relations found by the
+    /// MIR type-checker.
+    Subtype,
+
+    /// The constraint arose from reborrowing a path that goes
+    /// through a reference (e.g., `&mut *x`).
+    Reborrow,
+
+    /// Like `Reborrow`, but the reference being reborrowed is a
+    /// shared reference to a type with interior mutability (e.g.,
+    /// `&*x` where `x: &Cell<u32>`), so the referent may be mutated
+    /// while the new borrow is live.
+    InteriorMutableReborrow,
+
+    /// The constraint was supplied by the caller of constraint
+    /// generation, rather than derived from the MIR.
+    Seed,
+}
+
 impl<'a, 'gcx, 'tcx> RegionInferenceContext<'tcx> {
     /// Creates a new region inference context with a total of
     /// `num_region_variables` valid inference variables; the first N
@@ -124,6 +206,12 @@ impl<'a, 'gcx, 'tcx> RegionInferenceContext<'tcx> {
         let mut result = Self {
             definitions: definitions,
             constraints: Vec::new(),
+            constraint_keys: FxHashMap(),
+            point_blame: None,
+            propagation_iterations: 0,
+            live_points: None,
+            current_block: None,
+            live_points_count: 0,
         };
 
         result.init_free_regions(free_regions, mir);
@@ -222,17 +310,501 @@ impl<'a, 'gcx, 'tcx> RegionInferenceContext<'tcx> {
         self.definitions[r].value.contains_point(p)
     }
 
+    /// Returns true if, in the current region values, `sup` outlives
+    /// `sub` at `point`: that is, if `sup` already contains every point
+    /// (and free region) of `sub` that is reachable from `point`. This
+    /// is the relation that each outlives constraint requires.
+    ///
+    /// Until `solve()` executes, this value is not particularly meaningful.
+    pub fn region_contains(
+        &self,
+        mir: &Mir<'tcx>,
+        sup: RegionVid,
+        sub: RegionVid,
+        point: Location,
+    ) -> bool {
+        let mut sup_value = self.definitions[sup].value.clone();
+        let changed = Dfs::new(mir).copy(
+            &self.definitions[sub].value,
+            &mut sup_value,
+            point,
+            &mut vec![],
+        );
+        !changed
+    }
+
+    /// Returns the number of rounds of constraint propagation that
+    /// `solve()` took to reach a fixed point.
+    pub fn propagation_iterations(&self) -> usize {
+        self.propagation_iterations
+    }
+
+    /// Returns the `(sup, sub, point, span)` of each outlives
+    /// constraint, in a deterministic (sorted) order.
+    pub fn outlives_edges<'s>(
+        &'s self,
+    ) -> impl Iterator<Item = (RegionVid, RegionVid, Location, Span)> + 's {
+        let mut constraints: Vec<_> = self.constraints.iter().collect();
+        constraints.sort();
+        constraints
+            .into_iter()
+            .map(|constraint| (constraint.sup, constraint.sub, constraint.point, constraint.span))
+    }
+
+    /// Returns the number of (distinct) outlives constraints that have
+    /// been added.
+    pub fn constraints_len(&self) -> usize {
+        self.constraints.len()
+    }
+
+    /// Returns the number of distinct live points that have been added
+    /// with `add_live_point`. The free regions, which contain every
+    /// point from the start, are not counted.
+    pub fn live_points_len(&self) -> usize {
+        self.live_points_count
+    }
+
+    /// Returns the span of the constraint that first caused the point
+    /// `p` to be added to the value of `r` during `solve()`, if any.
+    /// Returns `None` if `p` is not in the value of `r`, or if it is
+    /// there because `r` is live at `p` rather than because of an
+    /// outlives constraint, or if `record_point_blame` was not called.
+    pub fn blame_span(&self, r: RegionVid, p: Location) -> Option<Span> {
+        self.point_blame
+            .as_ref()
+            .and_then(|point_blame| point_blame.get(&(r, p)).cloned())
+    }
+
+    /// Explains why the point `point` ended up in the value of `sup`
+    /// by finding the shortest chain of outlives constraints through
+    /// which it was propagated. The chain starts at an origin -- a
+    /// constant region, or a region that contains `point` without any
+    /// constraint having carried it there (i.e., because the region is
+    /// live at `point`) -- and ends with `sup`. Each region is paired
+    /// with the span of the constraint that carried `point` into it;
+    /// the origin is paired with `DUMMY_SP`. Returns an empty vector if
+    /// `point` is not in the value of `sup`.
+    ///
+    /// Until `solve()` executes, this value is not particularly meaningful.
+    pub fn explain_path(
+        &self,
+        mir: &Mir<'tcx>,
+        sup: RegionVid,
+        point: Location,
+    ) -> Vec<(RegionVid, Span)> {
+        self.explain_element(mir, sup, RegionElement::Location(point))
+    }
+
+    /// Like `explain_path`, but explains why the free region `fr` (that
+    /// is, `end(fr)`) ended up in the value of `sup`.
+    pub fn explain_free_region_path(
+        &self,
+        mir: &Mir<'tcx>,
+        sup: RegionVid,
+        fr: RegionVid,
+    ) -> Vec<(RegionVid, Span)> {
+        self.explain_element(mir, sup, RegionElement::FreeRegion(fr))
+    }
+
+    fn explain_element(
+        &self,
+        mir: &Mir<'tcx>,
+        sup: RegionVid,
+        element: RegionElement,
+    ) -> Vec<(RegionVid, Span)> {
+        if !self.definitions[sup].value.contains_element(element) {
+            return vec![];
+        }
+
+        // Walk the constraints backwards, from `sup` towards the
+        // regions it outlives, following only those constraints that
+        // carry `element`. For each region reached, remember the
+        // region we came from and the span of the constraint between
+        // them. A region that no constraint carries `element` into
+        // contains it for some other reason, so it is an origin.
+        let mut predecessors: FxHashMap<RegionVid, (RegionVid, Span)> = FxHashMap();
+        let mut queue = VecDeque::new();
+        queue.push_back(sup);
+        let mut origin = None;
+        while let Some(r) = queue.pop_front() {
+            if self.definitions[r].constant {
+                origin = Some(r);
+                break;
+            }
+
+            let mut carried = false;
+            for constraint in &self.constraints {
+                if constraint.sup != r || !self.constraint_carries(mir, constraint, element) {
+                    continue;
+                }
+                carried = true;
+
+                let sub = constraint.sub;
+                if sub == sup || predecessors.contains_key(&sub) {
+                    continue;
+                }
+                predecessors.insert(sub, (r, constraint.span));
+                queue.push_back(sub);
+            }
+
+            if !carried {
+                origin = Some(r);
+                break;
+            }
+        }
+
+        let mut path = vec![];
+        let mut r = match origin {
+            Some(r) => r,
+            None => return path,
+        };
+        let mut span = DUMMY_SP;
+        while let Some(&(next, next_span)) = predecessors.get(&r) {
+            path.push((r, span));
+            r = next;
+            span = next_span;
+        }
+        path.push((r, span));
+        path
+    }
+
+    /// True if propagating `constraint` carries `element` from the
+    /// value of its `sub` into the value of its `sup`: that is, if
+    /// `element` is in the part of the `sub`'s value that is reachable
+    /// from the point of the constraint.
+    fn constraint_carries(
+        &self,
+        mir: &Mir<'tcx>,
+        constraint: &Constraint,
+        element: RegionElement,
+    ) -> bool {
+        if !self.definitions[constraint.sub].value.contains_element(element) {
+            return false;
+        }
+
+        let mut reachable = Region::default();
+        Dfs::new(mir).copy(
+            &self.definitions[constraint.sub].value,
+            &mut reachable,
+            constraint.point,
+            &mut vec![],
+        );
+        reachable.contains_element(element)
+    }
+
     /// Returns access to the value of `r` for debugging purposes.
     pub(super) fn region_value(&self, r: RegionVid) -> &fmt::Debug {
         &self.definitions[r].value
     }
 
+    /// Returns a human-readable description of the value of `r`, in
+    /// which runs of consecutive points within a basic block are
+    /// collapsed into ranges (e.g., `{bb0[0..=2], bb3[1], '_#1r}`).
+    ///
+    /// Until `solve()` executes, this value is not particularly meaningful.
+    pub fn region_value_str(&self, r: RegionVid) -> String {
+        let value = &self.definitions[r].value;
+        let mut parts = point_runs_strs(&value.points);
+
+        for fr in &value.free_regions {
+            parts.push(format!("{:?}", fr));
+        }
+
+        format!("{{{}}}", parts.join(", "))
+    }
+
+    /// Writes out the value of each region variable, one per line, in
+    /// the form produced by `region_value_str`. The output depends only
+    /// on the region values, so it is suitable for comparing the
+    /// results of region inference across compiler versions.
+    pub fn write_region_values(&self, out: &mut Write) -> io::Result<()> {
+        for region in self.regions() {
+            writeln!(out, "{:?}: {}", region, self.region_value_str(region))?;
+        }
+        Ok(())
+    }
+
+    /// Writes out the region constraint graph in graphviz format. Each
+    /// node is a region variable, labeled with its current value, and
+    /// each edge `sup -> sub` is an outlives constraint, labeled with
+    /// the point at which it applies and its category. If `filter` is
+    /// given, only constraints of that category are included.
+    pub fn write_graphviz(
+        &self,
+        name: &str,
+        filter: Option<ConstraintCategory>,
+        out: &mut Write,
+    ) -> io::Result<()> {
+        writeln!(out, "digraph {} {{", name)?;
+        writeln!(out, r#"    graph [fontname="monospace"];"#)?;
+        writeln!(out, r#"    node [fontname="monospace", shape="box"];"#)?;
+        writeln!(out, r#"    edge [fontname="monospace"];"#)?;
+
+        for region in self.regions() {
+            writeln!(
+                out,
+                r#"    r{} [label="{:?}
+{:?}"];"#,
+                region.index(),
+                region,
+                self.definitions[region].value
+            )?;
+        }
+
+        let mut constraints: Vec<_> = self.constraints
+            .iter()
+            .filter(|constraint| filter.map_or(true, |category| constraint.category == category))
+            .collect();
+        constraints.sort();
+        for constraint in constraints {
+            writeln!(
+                out,
+                r#"    r{} -> r{} [label="{:?} {:?}"];"#,
+                constraint.sup.index(),
+                constraint.sub.index(),
+                constraint.point,
+                constraint.category
+            )?;
+        }
+
+        writeln!(out, "}}")
+    }
+
+    /// Writes out one line per region variable giving its origin
+    /// (`free`, `local`, `return` or `location`), the number of
+    /// distinct points at which it was found to be live, and the span
+    /// blamed for the last point in its value (or `-` if that point
+    /// was not added by propagating a constraint). This is used by
+    /// `-Z dump-nll-summary`, which keeps the live points.
+    pub fn write_summary(&self, codemap: &CodeMap, out: &mut Write) -> io::Result<()> {
+        let live_points: FxHashSet<(RegionVid, Location)> = self.kept_live_points()
+            .iter()
+            .map(|&(region, point, _)| (region, point))
+            .collect();
+
+        for region in self.regions() {
+            let definition = &self.definitions[region];
+            let origin = match definition.origin {
+                RegionVariableOrigin::NLL(NLLRegionVariableOrigin::FreeRegion) => "free",
+                RegionVariableOrigin::NLL(NLLRegionVariableOrigin::Inferred(ty_context)) => {
+                    match ty_context {
+                        TyContext::LocalDecl { .. } => "local",
+                        TyContext::ReturnTy(..) => "return",
+                        TyContext::Location(..) => "location",
+                    }
+                }
+                _ => "other",
+            };
+            let live_point_count = live_points
+                .iter()
+                .filter(|&&(live_region, _)| live_region == region)
+                .count();
+            let blame = definition
+                .value
+                .points
+                .iter()
+                .next_back()
+                .and_then(|&point| self.blame_span(region, point))
+                .map(|span| codemap.span_to_string(span))
+                .unwrap_or_else(|| String::from("-"));
+            writeln!(out, "{:?} {} {} {}", region, origin, live_point_count, blame)?;
+        }
+
+        Ok(())
+    }
+
+    /// Records the liveness points and outlives constraints
+    /// accumulated so far into `all_facts`. This should be called
+    /// before `solve()`, so that the region values consist only of
+    /// the points at which each region is live. If `filter` is given,
+    /// only outlives constraints of that category are recorded.
+    pub(super) fn add_facts(&self, all_facts: &mut AllFacts, filter: Option<ConstraintCategory>) {
+        for region in self.regions() {
+            for &point in &self.definitions[region].value.points {
+                all_facts.region_live_at.push((region, point));
+            }
+        }
+
+        for constraint in &self.constraints {
+            if filter.map_or(false, |category| constraint.category != category) {
+                continue;
+            }
+            all_facts
+                .outlives
+                .push((constraint.sup, constraint.sub, constraint.point));
+        }
+    }
+
+    /// Starts recording which constraint added each point to each
+    /// region's value, so that it can be queried with `blame_span`.
+    /// This costs a map entry per propagated point, so it is only done
+    /// when asked for.
+    pub(super) fn record_point_blame(&mut self) {
+        self.point_blame = Some(FxHashMap());
+    }
+
+    /// Starts keeping each live point given to `add_live_point`, so
+    /// that blocks can later be retracted (see `retract_blocks`) and
+    /// the live points reported. This costs an entry per live point,
+    /// so it is only done when one of those is needed.
+    pub(super) fn keep_live_points(&mut self) {
+        self.live_points = Some(vec![]);
+    }
+
+    /// Returns the live points kept since `keep_live_points` was
+    /// called. It is a bug to ask for them otherwise.
+    fn kept_live_points(&self) -> &[(RegionVid, Location, Option<BasicBlock>)] {
+        match self.live_points {
+            Some(ref live_points) => live_points,
+            None => bug!("live points were not kept; `keep_live_points` must be called first"),
+        }
+    }
+
+    /// Returns the free regions that correspond to a lifetime
+    /// parameter written by the user, but which have not been given
+    /// any live points or appeared in any outlives constraint. These
+    /// are used by `-Z nll-warn-unconstrained`, which keeps the live
+    /// points.
+    pub(super) fn unconstrained_named_regions(&self) -> Vec<(RegionVid, ty::Region<'tcx>)> {
+        let mut constrained: FxHashSet<RegionVid> = FxHashSet();
+        for &(region, _, _) in self.kept_live_points() {
+            constrained.insert(region);
+        }
+        for constraint in &self.constraints {
+            constrained.insert(constraint.sup);
+            constrained.insert(constraint.sub);
+        }
+
+        self.definitions
+            .iter_enumerated()
+            .filter_map(|(region, definition)| {
+                let name = match definition.name {
+                    Some(name) => name,
+                    None => return None,
+                };
+                let is_lifetime_param = match *name {
+                    ty::ReEarlyBound(..) => true,
+                    ty::ReFree(ref free_region) => match free_region.bound_region {
+                        ty::BrNamed(..) => true,
+                        _ => false,
+                    },
+                    _ => false,
+                };
+                if is_lifetime_param && !constrained.contains(&region) {
+                    Some((region, name))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Returns each region that is live at more than `threshold`
+    /// distinct points, along with the number of those points. Such
+    /// regions are expensive to propagate, and are often the result
+    /// of a reference being kept live for longer than necessary. This
+    /// is used by `-Z nll-report-hot-regions`, which keeps the live
+    /// points.
+    pub(super) fn hot_regions(&self, threshold: usize) -> Vec<(RegionVid, usize)> {
+        let mut live_points: FxHashMap<RegionVid, FxHashSet<Location>> = FxHashMap();
+        for &(region, point, _) in self.kept_live_points() {
+            live_points.entry(region).or_insert_with(FxHashSet).insert(point);
+        }
+
+        let mut hot: Vec<_> = live_points
+            .into_iter()
+            .map(|(region, points)| (region, points.len()))
+            .filter(|&(_, count)| count > threshold)
+            .collect();
+        hot.sort();
+        hot
+    }
+
+    /// Checks that the solved region values satisfy every outlives
+    /// constraint whose `sup` is not a constant: that is, that each
+    /// such `sup` contains whatever part of its `sub` is reachable from
+    /// the point of the constraint (see `region_contains`). Constraints
+    /// on constants are skipped, since their violations are reported
+    /// as errors by `solve()`. This is used by `-Z verify-nll-regions`
+    /// to catch bugs in constraint propagation.
+    pub(super) fn verify_constraints(&self, mir: &Mir<'tcx>) {
+        for constraint in &self.constraints {
+            if self.definitions[constraint.sup].constant {
+                continue;
+            }
+
+            if !self.region_contains(mir, constraint.sup, constraint.sub, constraint.point) {
+                bug!(
+                    "constraint {:?} does not hold: {:?} is {:?}, but {:?} is {:?}",
+                    constraint,
+                    constraint.sup,
+                    self.definitions[constraint.sup].value,
+                    constraint.sub,
+                    self.definitions[constraint.sub].value
+                );
+            }
+        }
+    }
+
+    /// Returns the number of outlives constraints, and of live points
+    /// kept (duplicates included), that have been added so far. This
+    /// marks the start of what a later constraint generation adds, for
+    /// `verify_streamed_constraints`. The live points must have been
+    /// kept (see `keep_live_points`).
+    pub(super) fn constraints_mark(&self) -> (usize, usize) {
+        (self.constraints.len(), self.kept_live_points().len())
+    }
+
+    /// Checks, for `-Z nll-verify-stream`, that `live_points` and
+    /// `outlives` are exactly the live points and outlives constraints
+    /// that were added since `mark` (see `constraints_mark`). An
+    /// outlives constraint that was already present before `mark`
+    /// (e.g., from subtyping) is not added again, so for such
+    /// constraints it is enough that they are present at all.
+    pub(super) fn verify_streamed_constraints(
+        &self,
+        mark: (usize, usize),
+        live_points: &FxHashSet<(RegionVid, Location)>,
+        outlives: &FxHashSet<(RegionVid, RegionVid, Location)>,
+    ) {
+        let (constraints_len, live_points_len) = mark;
+
+        let added_live_points: FxHashSet<_> = self.kept_live_points()[live_points_len..]
+            .iter()
+            .map(|&(region, point, _)| (region, point))
+            .collect();
+        if added_live_points != *live_points {
+            bug!(
+                "streamed {} distinct live points, but {} were added",
+                live_points.len(),
+                added_live_points.len()
+            );
+        }
+
+        for constraint in &self.constraints[constraints_len..] {
+            if !outlives.contains(&(constraint.sup, constraint.sub, constraint.point)) {
+                bug!("constraint {:?} was added, but not streamed", constraint);
+            }
+        }
+        for &(sup, sub, point) in outlives {
+            if !self.constraint_keys.contains_key(&(sup, sub, point)) {
+                bug!("constraint {:?}: {:?} @ {:?} was streamed, but not added", sup, sub, point);
+            }
+        }
+    }
+
     /// Indicates that the region variable `v` is live at the point `point`.
     pub(super) fn add_live_point(&mut self, v: RegionVid, point: Location) {
         debug!("add_live_point({:?}, {:?})", v, point);
+        self.debug_assert_region_exists(v, point);
+        if let Some(ref mut live_points) = self.live_points {
+            live_points.push((v, point, self.current_block));
+        }
         let definition = &mut self.definitions[v];
         if !definition.constant {
-            definition.value.add_point(point);
+            if definition.value.add_point(point) {
+                self.live_points_count += 1;
+            }
         } else {
             // Constants are used for free regions, which already
             // contain all the points in the control-flow graph.
@@ -244,26 +816,163 @@ impl<'a, 'gcx, 'tcx> RegionInferenceContext<'tcx> {
     pub(super) fn add_outlives(
         &mut self,
         span: Span,
+        category: ConstraintCategory,
         sup: RegionVid,
         sub: RegionVid,
         point: Location,
     ) {
-        debug!("add_outlives({:?}: {:?} @ {:?}", sup, sub, point);
+        self.add_outlives_if_new(span, category, sup, sub, point);
+    }
+
+    /// Like `add_outlives`, but returns true only if the constraint
+    /// `sup: sub @ point` was not already present.
+    pub(super) fn add_outlives_if_new(
+        &mut self,
+        span: Span,
+        category: ConstraintCategory,
+        sup: RegionVid,
+        sub: RegionVid,
+        point: Location,
+    ) -> bool {
+        debug!("add_outlives({:?}: {:?} @ {:?}) due to {:?}", sup, sub, point, category);
+        self.debug_assert_region_exists(sup, point);
+        self.debug_assert_region_exists(sub, point);
+        if let Some(&index) = self.constraint_keys.get(&(sup, sub, point)) {
+            // For diagnostics, prefer a span in the user's code over
+            // one produced by a macro expansion.
+            let constraint = &mut self.constraints[index];
+            if is_from_expansion(constraint.span) && !is_from_expansion(span) {
+                debug!("add_outlives: duplicate constraint, replacing span");
+                constraint.span = span;
+            } else {
+                debug!("add_outlives: duplicate constraint, skipping");
+            }
+            return false;
+        }
+        self.constraint_keys
+            .insert((sup, sub, point), self.constraints.len());
         self.constraints.push(Constraint {
             span,
+            category,
+            block: self.current_block,
             sup,
             sub,
             point,
         });
+        true
+    }
+
+    /// Tags the live points and constraints added from now on with
+    /// `block` (or with no block, if `None`), so that they can later
+    /// be removed by `retract_blocks`.
+    pub(super) fn set_current_block(&mut self, block: Option<BasicBlock>) {
+        self.current_block = block;
+    }
+
+    /// Removes every live point and constraint that was added on
+    /// behalf of one of `blocks`, and resets the values of all
+    /// non-constant regions to just their remaining live points. This
+    /// undoes the effect of `solve()` as well, so the caller must
+    /// invoke it again once new constraints have been added. The live
+    /// points must have been kept (see `keep_live_points`).
+    pub(super) fn retract_blocks(&mut self, blocks: &FxHashSet<BasicBlock>) {
+        debug!("retract_blocks({:?})", blocks);
+
+        let is_retracted = |block: Option<BasicBlock>| match block {
+            Some(block) => blocks.contains(&block),
+            None => false,
+        };
+
+        self.constraints.retain(|constraint| !is_retracted(constraint.block));
+        self.constraint_keys = self.constraints
+            .iter()
+            .enumerate()
+            .map(|(index, constraint)| ((constraint.sup, constraint.sub, constraint.point), index))
+            .collect();
+        match self.live_points {
+            Some(ref mut live_points) => {
+                live_points.retain(|&(_, _, block)| !is_retracted(block));
+            }
+            None => bug!("retract_blocks: live points were not kept"),
+        }
+        self.reset_region_values();
+    }
+
+    /// Resets the value of every non-constant region to just its
+    /// live points, discarding the results of any earlier `solve()`.
+    fn reset_region_values(&mut self) {
+        if let Some(ref mut point_blame) = self.point_blame {
+            point_blame.clear();
+        }
+
+        for definition in self.definitions.iter_mut() {
+            if !definition.constant {
+                definition.value = Region::default();
+            }
+        }
+        let live_points = match self.live_points {
+            Some(ref live_points) => live_points,
+            None => bug!("reset_region_values: live points were not kept"),
+        };
+        self.live_points_count = 0;
+        for &(region, point, _) in live_points {
+            let definition = &mut self.definitions[region];
+            if !definition.constant && definition.value.add_point(point) {
+                self.live_points_count += 1;
+            }
+        }
+    }
+
+    /// Checks (in debug builds) that `r` is one of the region
+    /// variables this context was created with. A region from some
+    /// other inference context would otherwise cause a confusing
+    /// out-of-bounds panic, or silently alias an unrelated variable.
+    fn debug_assert_region_exists(&self, r: RegionVid, point: Location) {
+        debug_assert!(
+            r.index() < self.definitions.len(),
+            "region {:?} used at {:?} is not registered with this context \
+             (only {} region variables exist)",
+            r,
+            point,
+            self.definitions.len()
+        );
+    }
+
+    /// Checks that every region appearing in the types of the locals
+    /// of `mir` is registered with this context. This catches a `mir`
+    /// that was renumbered against a different inference context than
+    /// the one this context was created from, before constraint
+    /// generation produces nonsensical constraints. This walks every
+    /// local, so callers only do it in debug builds.
+    pub(super) fn debug_assert_mir_regions_exist(
+        &self,
+        infcx: &InferCtxt<'a, 'gcx, 'tcx>,
+        mir: &Mir<'tcx>,
+    ) {
+        for (local, local_decl) in mir.local_decls.iter_enumerated() {
+            infcx.tcx.for_each_free_region(&local_decl.ty, |region| {
+                if let ty::ReVar(vid) = *region {
+                    assert!(
+                        vid.index() < self.definitions.len(),
+                        "region {:?} in the type of {:?} is not registered with this context \
+                         (only {} region variables exist)",
+                        vid,
+                        local,
+                        self.definitions.len()
+                    );
+                }
+            });
+        }
     }
 
     /// Perform region inference.
     pub(super) fn solve(&mut self, infcx: &InferCtxt<'a, 'gcx, 'tcx>, mir: &Mir<'tcx>) {
-        let errors = self.propagate_constraints(mir);
+        let max_iterations = infcx.tcx.sess.opts.debugging_opts.nll_max_iterations;
+        let errors = self.propagate_constraints(mir, max_iterations);
 
         // worst error msg ever
-        for (fr1, span, fr2) in errors {
-            infcx.tcx.sess.span_err(
+        for (fr1, span, fr2, sub) in errors {
+            let mut err = infcx.tcx.sess.struct_span_err(
                 span,
                 &format!(
                     "free region `{}` does not outlive `{}`",
@@ -271,6 +980,33 @@ impl<'a, 'gcx, 'tcx> RegionInferenceContext<'tcx> {
                     self.definitions[fr2].name.unwrap()
                 ),
             );
+
+            // If asked to, explain how `fr2` got into the region that
+            // `fr1` was required to outlive, one constraint at a time.
+            if infcx.tcx.sess.opts.debugging_opts.nll_explain {
+                let path = self.explain_free_region_path(mir, sub, fr2);
+                for (&(outlived, _), &(region, span)) in path.iter().zip(path.iter().skip(1)) {
+                    err.span_note(
+                        span,
+                        &format!(
+                            "`{}` must outlive `{}` because of this",
+                            self.region_name(region),
+                            self.region_name(outlived)
+                        ),
+                    );
+                }
+            }
+
+            err.emit();
+        }
+    }
+
+    /// Returns the name of `r` if it is a named free region, and its
+    /// `'_#Nr` form otherwise.
+    fn region_name(&self, r: RegionVid) -> String {
+        match self.definitions[r].name {
+            Some(name) => format!("{}", name),
+            None => format!("{:?}", r),
         }
     }
 
@@ -278,11 +1014,20 @@ impl<'a, 'gcx, 'tcx> RegionInferenceContext<'tcx> {
     /// for each region variable until all the constraints are
     /// satisfied. Note that some values may grow **too** large to be
     /// feasible, but we check this later.
-    fn propagate_constraints(&mut self, mir: &Mir<'tcx>) -> Vec<(RegionVid, Span, RegionVid)> {
+    ///
+    /// If `max_iterations` is given and the values have still not
+    /// converged after that many rounds, this reports a compiler bug
+    /// naming the regions that changed in the last round.
+    fn propagate_constraints(
+        &mut self,
+        mir: &Mir<'tcx>,
+        max_iterations: Option<usize>,
+    ) -> Vec<(RegionVid, Span, RegionVid, RegionVid)> {
         let mut changed = true;
         let mut dfs = Dfs::new(mir);
         let mut error_regions = FxHashSet();
         let mut errors = vec![];
+        let mut new_points = vec![];
 
         debug!("propagate_constraints()");
         debug!("propagate_constraints: constraints={:#?}", {
@@ -291,8 +1036,23 @@ impl<'a, 'gcx, 'tcx> RegionInferenceContext<'tcx> {
             constraints
         });
 
+        let mut changed_regions = BTreeSet::new();
+        self.propagation_iterations = 0;
         while changed {
+            if let Some(max_iterations) = max_iterations {
+                if self.propagation_iterations >= max_iterations {
+                    bug!(
+                        "region inference did not converge after {} iterations; \
+                         regions changed in the last iteration: {:?}",
+                        self.propagation_iterations,
+                        changed_regions
+                    );
+                }
+            }
+
             changed = false;
+            changed_regions.clear();
+            self.propagation_iterations += 1;
             for constraint in &self.constraints {
                 debug!("propagate_constraints: constraint={:?}", constraint);
                 let sub = &self.definitions[constraint.sub].value.clone();
@@ -305,8 +1065,17 @@ impl<'a, 'gcx, 'tcx> RegionInferenceContext<'tcx> {
                     // If this is not a constant, then grow the value as needed to
                     // accommodate the outlives constraint.
 
-                    if dfs.copy(sub, &mut sup_def.value, constraint.point) {
+                    if dfs.copy(sub, &mut sup_def.value, constraint.point, &mut new_points) {
                         changed = true;
+                        changed_regions.insert(constraint.sup);
+                    }
+
+                    for point in new_points.drain(..) {
+                        if let Some(ref mut point_blame) = self.point_blame {
+                            point_blame
+                                .entry((constraint.sup, point))
+                                .or_insert(constraint.span);
+                        }
                     }
 
                     debug!("propagate_constraints:    sup (after) : {:?}", sup_def.value);
@@ -317,7 +1086,7 @@ impl<'a, 'gcx, 'tcx> RegionInferenceContext<'tcx> {
                     // satisfied. If so, create an error.
 
                     let mut sup_value = sup_def.value.clone();
-                    if dfs.copy(sub, &mut sup_value, constraint.point) {
+                    if dfs.copy(sub, &mut sup_value, constraint.point, &mut new_points) {
                         // Constant values start out with the entire
                         // CFG, so it must be some new free region
                         // that was added. Find one.
@@ -328,9 +1097,15 @@ impl<'a, 'gcx, 'tcx> RegionInferenceContext<'tcx> {
                             .unwrap();
                         debug!("propagate_constraints:    new_region : {:?}", new_region);
                         if error_regions.insert(constraint.sup) {
-                            errors.push((constraint.sup, constraint.span, new_region));
+                            errors.push((
+                                constraint.sup,
+                                constraint.span,
+                                new_region,
+                                constraint.sub,
+                            ));
                         }
                     }
+                    new_points.clear();
                 }
             }
             debug!("\n");
@@ -339,6 +1114,48 @@ impl<'a, 'gcx, 'tcx> RegionInferenceContext<'tcx> {
     }
 }
 
+/// Describes the sorted set `points`, collapsing runs of consecutive
+/// points within a basic block into ranges (e.g., `bb0[0..=2]`).
+pub(super) fn point_runs_strs(points: &BTreeSet<Location>) -> Vec<String> {
+    let mut parts = vec![];
+
+    let mut open_run: Option<(BasicBlock, usize, usize)> = None;
+    for &Location { block, statement_index } in points {
+        open_run = match open_run {
+            Some((run_block, start, end))
+                if run_block == block && end + 1 == statement_index =>
+            {
+                Some((run_block, start, statement_index))
+            }
+
+            Some(run) => {
+                parts.push(point_run_str(run));
+                Some((block, statement_index, statement_index))
+            }
+
+            None => Some((block, statement_index, statement_index)),
+        };
+    }
+    if let Some(run) = open_run {
+        parts.push(point_run_str(run));
+    }
+
+    parts
+}
+
+fn point_run_str((block, start, end): (BasicBlock, usize, usize)) -> String {
+    if start == end {
+        format!("{:?}[{}]", block, start)
+    } else {
+        format!("{:?}[{}..={}]", block, start, end)
+    }
+}
+
+/// True if `span` was produced by a macro expansion.
+fn is_from_expansion(span: Span) -> bool {
+    span.ctxt().outer().expn_info().is_some()
+}
+
 struct Dfs<'a, 'tcx: 'a> {
     mir: &'a Mir<'tcx>,
 }
@@ -353,6 +1170,7 @@ impl<'a, 'tcx> Dfs<'a, 'tcx> {
         from_region: &Region,
         to_region: &mut Region,
         start_point: Location,
+        new_points: &mut Vec<Location>,
     ) -> bool {
         let mut changed = false;
 
@@ -373,7 +1191,10 @@ impl<'a, 'tcx> Dfs<'a, 'tcx> {
                 continue;
             }
 
-            changed |= to_region.add_point(p);
+            if to_region.add_point(p) {
+                changed = true;
+                new_points.push(p);
+            }
 
             let block_data = &self.mir[p.block];
             let successor_points = if p.statement_index < block_data.statements.len() {
@@ -433,10 +1254,11 @@ impl fmt::Debug for Constraint {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         write!(
             formatter,
-            "({:?}: {:?} @ {:?}) due to {:?}",
+            "({:?}: {:?} @ {:?}) due to {:?} at {:?}",
             self.sup,
             self.sub,
             self.point,
+            self.category,
             self.span
         )
     }
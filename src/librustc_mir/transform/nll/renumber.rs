@@ -60,6 +60,12 @@ impl<'a, 'gcx, 'tcx> NLLVisitor<'a, 'gcx, 'tcx> {
     /// Replaces all regions appearing in `value` with fresh inference
     /// variables. This is what we do for almost the entire MIR, with
     /// the exception of the declared types of our arguments.
+    ///
+    /// This includes the types of locals with a user-written type
+    /// annotation (e.g., `let x: &'a u32`). MIR does not record such
+    /// annotations in a statement of their own, so a lifetime named
+    /// in one is replaced here like any other region; it is only
+    /// enforced by the type checker, which works on the HIR.
     fn renumber_regions<T>(&mut self, ty_context: TyContext, value: &T) -> T
     where
         T: TypeFoldable<'tcx>,
@@ -8,21 +8,23 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use rustc::mir::Mir;
+use rustc::mir::{BasicBlock, Mir};
 use rustc::infer::region_constraints::Constraint;
 use rustc::infer::region_constraints::RegionConstraintData;
 use rustc::ty;
+use rustc::util::nodemap::FxHashSet;
 use transform::type_check::MirTypeckRegionConstraints;
 use transform::type_check::OutlivesSet;
 
 use super::free_regions::FreeRegions;
-use super::region_infer::RegionInferenceContext;
+use super::region_infer::{ConstraintCategory, RegionInferenceContext};
 
 /// When the MIR type-checker executes, it validates all the types in
 /// the MIR, and in the process generates a set of constraints that
 /// must hold regarding the regions in the MIR, along with locations
 /// *where* they must hold. This code takes those constriants and adds
-/// them into the NLL `RegionInferenceContext`.
+/// them into the NLL `RegionInferenceContext`. Each is tagged with the
+/// basic block of the location where it must hold.
 pub(super) fn generate<'tcx>(
     regioncx: &mut RegionInferenceContext<'tcx>,
     free_regions: &FreeRegions<'tcx>,
@@ -33,6 +35,26 @@ pub(super) fn generate<'tcx>(
         regioncx,
         free_regions,
         mir,
+        blocks: None,
+    }.generate(constraints);
+}
+
+/// Like `generate`, but only adds the constraints that must hold in
+/// one of `blocks`. This is used to regenerate the constraints of
+/// blocks whose contributions were removed with
+/// `RegionInferenceContext::retract_blocks`.
+pub(super) fn regenerate<'tcx>(
+    regioncx: &mut RegionInferenceContext<'tcx>,
+    free_regions: &FreeRegions<'tcx>,
+    mir: &Mir<'tcx>,
+    constraints: &MirTypeckRegionConstraints<'tcx>,
+    blocks: &FxHashSet<BasicBlock>,
+) {
+    SubtypeConstraintGenerator {
+        regioncx,
+        free_regions,
+        mir,
+        blocks: Some(blocks),
     }.generate(constraints);
 }
 
@@ -40,6 +62,10 @@ struct SubtypeConstraintGenerator<'cx, 'tcx: 'cx> {
     regioncx: &'cx mut RegionInferenceContext<'tcx>,
     free_regions: &'cx FreeRegions<'tcx>,
     mir: &'cx Mir<'tcx>,
+
+    /// If given, only the constraints that must hold in these blocks
+    /// are added.
+    blocks: Option<&'cx FxHashSet<BasicBlock>>,
 }
 
 impl<'cx, 'tcx> SubtypeConstraintGenerator<'cx, 'tcx> {
@@ -56,13 +82,23 @@ impl<'cx, 'tcx> SubtypeConstraintGenerator<'cx, 'tcx> {
         );
 
         for (region, location) in liveness_set {
+            if !self.includes_block(location.block) {
+                continue;
+            }
+
             debug!("generate: {:#?} is live at {:#?}", region, location);
             let region_vid = self.to_region_vid(region);
+            self.regioncx.set_current_block(Some(location.block));
             self.regioncx.add_live_point(region_vid, *location);
         }
 
         for OutlivesSet { locations, data } in outlives_sets {
+            if !self.includes_block(locations.at_location.block) {
+                continue;
+            }
+
             debug!("generate: constraints at: {:#?}", locations);
+            self.regioncx.set_current_block(Some(locations.at_location.block));
             let RegionConstraintData {
                 constraints,
                 verifys,
@@ -86,8 +122,13 @@ impl<'cx, 'tcx> SubtypeConstraintGenerator<'cx, 'tcx> {
                 // "outlives" (`>=`) whereas the region constraints
                 // talk about `<=`.
                 let span = self.mir.source_info(locations.from_location).span;
-                self.regioncx
-                    .add_outlives(span, b_vid, a_vid, locations.at_location);
+                self.regioncx.add_outlives(
+                    span,
+                    ConstraintCategory::Subtype,
+                    b_vid,
+                    a_vid,
+                    locations.at_location,
+                );
             }
 
             assert!(verifys.is_empty(), "verifys not yet implemented");
@@ -96,6 +137,12 @@ impl<'cx, 'tcx> SubtypeConstraintGenerator<'cx, 'tcx> {
                 "MIR type-checker does not use givens (thank goodness)"
             );
         }
+
+        self.regioncx.set_current_block(None);
+    }
+
+    fn includes_block(&self, block: BasicBlock) -> bool {
+        self.blocks.map_or(true, |blocks| blocks.contains(&block))
     }
 
     fn to_region_vid(&self, r: ty::Region<'tcx>) -> ty::RegionVid {
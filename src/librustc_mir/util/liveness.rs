@@ -123,7 +123,33 @@ pub fn liveness_of_locals<'tcx>(mir: &Mir<'tcx>, mode: LivenessMode) -> Liveness
     LivenessResult { mode, ins, outs }
 }
 
+/// The statement or terminator found at a given location within a
+/// basic block, as reported by `simulate_block_with_statements`.
+#[derive(Copy, Clone, Debug)]
+pub enum BlockPoint<'a, 'tcx: 'a> {
+    Statement(&'a Statement<'tcx>),
+    Terminator(&'a Terminator<'tcx>),
+}
+
 impl LivenessResult {
+    /// Returns results in which no local is live anywhere in `mir`.
+    /// These use a mode in which no use counts as making a local
+    /// live, so that `simulate_block` stays consistent with them.
+    pub fn empty<'tcx>(mir: &Mir<'tcx>) -> Self {
+        let locals = mir.local_decls.len();
+        let ins: IndexVec<_, _> = mir.basic_blocks()
+            .indices()
+            .map(|_| LocalSet::new_empty(locals))
+            .collect();
+        let outs = ins.clone();
+        let mode = LivenessMode {
+            include_regular_use: false,
+            include_drops: false,
+        };
+
+        LivenessResult { mode, ins, outs }
+    }
+
     /// Walks backwards through the statements/terminator in the given
     /// basic block `block`.  At each point within `block`, invokes
     /// the callback `op` with the current location and the set of
@@ -131,6 +157,22 @@ impl LivenessResult {
     pub fn simulate_block<'tcx, OP>(&self, mir: &Mir<'tcx>, block: BasicBlock, mut callback: OP)
     where
         OP: FnMut(Location, &LocalSet),
+    {
+        self.simulate_block_with_statements(mir, block, |location, _, bits| {
+            callback(location, bits)
+        });
+    }
+
+    /// Like `simulate_block`, but also passes the callback the
+    /// statement (or, for the final point of the block, the
+    /// terminator) found at each location.
+    pub fn simulate_block_with_statements<'a, 'tcx, OP>(
+        &self,
+        mir: &'a Mir<'tcx>,
+        block: BasicBlock,
+        mut callback: OP,
+    ) where
+        OP: FnMut(Location, BlockPoint<'a, 'tcx>, &LocalSet),
     {
         let data = &mir[block];
 
@@ -148,7 +190,7 @@ impl LivenessResult {
         };
         let terminator_defs_uses = self.defs_uses(mir, terminator_location, &data.terminator);
         terminator_defs_uses.apply(&mut bits);
-        callback(terminator_location, &bits);
+        callback(terminator_location, BlockPoint::Terminator(data.terminator()), &bits);
 
         // Compute liveness before each statement (in rev order) and invoke callback.
         for statement in data.statements.iter().rev() {
@@ -159,12 +201,44 @@ impl LivenessResult {
             };
             let statement_defs_uses = self.defs_uses(mir, statement_location, statement);
             statement_defs_uses.apply(&mut bits);
-            callback(statement_location, &bits);
+            callback(statement_location, BlockPoint::Statement(statement), &bits);
         }
 
         assert_eq!(bits, self.ins[block]);
     }
 
+    /// Returns true if `local` is live on entry to `location`. Rather
+    /// than simulating the entire block, this only walks backwards
+    /// from the end of the block as far as `location`.
+    pub fn is_live_at<'tcx>(&self, mir: &Mir<'tcx>, local: Local, location: Location) -> bool {
+        let Location { block, statement_index } = location;
+        if statement_index == 0 {
+            return self.ins[block].contains(&local);
+        }
+
+        let data = &mir[block];
+        let mut bits = self.outs[block].clone();
+
+        let terminator_location = Location {
+            block,
+            statement_index: data.statements.len(),
+        };
+        let terminator_defs_uses = self.defs_uses(mir, terminator_location, &data.terminator);
+        terminator_defs_uses.apply(&mut bits);
+
+        for index in (statement_index..data.statements.len()).rev() {
+            let statement_location = Location {
+                block,
+                statement_index: index,
+            };
+            let statement = &data.statements[index];
+            let statement_defs_uses = self.defs_uses(mir, statement_location, statement);
+            statement_defs_uses.apply(&mut bits);
+        }
+
+        bits.contains(&local)
+    }
+
     fn defs_uses<'tcx, V>(&self, mir: &Mir<'tcx>, location: Location, thing: &V) -> DefsUses
     where
         V: MirVisitable<'tcx>,
@@ -0,0 +1,43 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that both borrows stored into a tuple aggregate are kept alive
+// for as long as the tuple is used, and no longer.
+
+// compile-flags:-Zborrowck=compare -Znll
+
+#![allow(warnings)]
+
+fn main() {
+}
+
+fn nll_fail() {
+    let mut a = 1;
+    let mut b = 2;
+    let p = (&mut a, &mut b);
+    a = 3;
+    //~^ ERROR (Ast) [E0506]
+    //~| ERROR (Mir) [E0506]
+    b = 4;
+    //~^ ERROR (Ast) [E0506]
+    //~| ERROR (Mir) [E0506]
+    *p.0 += *p.1;
+}
+
+fn nll_ok() {
+    let mut a = 1;
+    let mut b = 2;
+    let p = (&mut a, &mut b);
+    *p.0 += *p.1;
+    a = 3;
+    //~^ ERROR (Ast) [E0506]
+    b = 4;
+    //~^ ERROR (Ast) [E0506]
+}
@@ -0,0 +1,29 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the regions in the types of the arguments are the free
+// regions of the signature, including when the argument is only moved
+// out of on the first statement.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+fn ok<'a>(x: &'a u32) -> &'a u32 {
+    x
+}
+
+fn too_long<'a>(x: &'a u32) -> &'static u32 {
+    x //~ ERROR free region `'a` does not outlive `'static`
+}
+
+fn unused<'a>(_x: &'a u32) { }
+
+fn main() { }
@@ -0,0 +1,38 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// Test that a reference returned by a call, and stored in the call's
+// destination, keeps the borrow of the argument alive for as long as
+// the destination is live.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+fn id<'a>(x: &'a u32) -> &'a u32 {
+    x
+}
+
+fn use_ref(_: &u32) { }
+
+fn nll_fail() {
+    let mut x = 22;
+    let r = id(&x);
+    x = 23; //~ ERROR cannot assign to `x` because it is borrowed [E0506]
+    use_ref(r);
+}
+
+fn nll_ok() {
+    let mut x = 22;
+    let r = id(&x);
+    use_ref(r);
+    x = 23;
+}
+
+fn main() { }
@@ -0,0 +1,40 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a closure which mutates `(*x).f` keeps the borrow of `x`
+// it captures alive for as long as the closure itself is live, and
+// no longer.
+
+// compile-flags:-Zborrowck=compare -Znll
+
+#![allow(warnings)]
+
+struct S {
+    f: u32,
+}
+
+fn main() {
+}
+
+fn nll_fail(x: &mut S) {
+    let mut c = || (*x).f += 1;
+    c();
+    (*x).f = 2;
+    //~^ ERROR (Ast) [E0506]
+    //~| ERROR (Mir) [E0506]
+    c();
+}
+
+fn nll_ok(x: &mut S) {
+    let mut c = || (*x).f += 1;
+    c();
+    (*x).f = 2;
+    //~^ ERROR (Ast) [E0506]
+}
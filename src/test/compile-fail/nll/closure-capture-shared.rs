@@ -0,0 +1,37 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the borrow of `x` captured by a closure is live from the
+// point where the closure is created for as long as the closure may
+// still be called.
+
+// compile-flags:-Zborrowck=compare -Znll
+
+#![allow(warnings)]
+
+fn main() {
+}
+
+fn nll_fail() {
+    let mut x = 1;
+    let c = || x + 1;
+    x = 2;
+    //~^ ERROR (Ast) [E0506]
+    //~| ERROR (Mir) [E0506]
+    c();
+}
+
+fn nll_ok() {
+    let mut x = 1;
+    let c = || x + 1;
+    c();
+    x = 2;
+    //~^ ERROR (Ast) [E0506]
+}
@@ -0,0 +1,27 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// Test that `-Z nll-conservative-reborrows` walks through shared
+// references as if they were mutable, adding constraints that are
+// otherwise omitted. Compare `deep` with the same function in
+// `reborrow-shared-outlives.rs`, which is accepted.
+
+// compile-flags:-Znll -Zborrowck=mir -Znll-conservative-reborrows
+
+#![allow(warnings)]
+
+fn deep<'a, 'b>(x: &'a &'b u32) -> &'b u32 {
+    &**x //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn deep_ok<'a, 'b>(x: &'a &'b u32) -> &'a u32 {
+    &**x
+}
+
+fn main() { }
@@ -0,0 +1,38 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that reading the discriminant of an enum through a shared
+// reference keeps the region of that reference live up to the read.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+fn nll_fail() {
+    let mut e = Some(22);
+    let r = &e;
+    e = None; //~ ERROR cannot assign to `e` because it is borrowed [E0506]
+    match *r {
+        Some(_) => { }
+        None => { }
+    }
+}
+
+fn nll_ok() {
+    let mut e = Some(22);
+    let r = &e;
+    match *r {
+        Some(_) => { }
+        None => { }
+    }
+    e = None;
+}
+
+fn main() { }
@@ -0,0 +1,94 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that when a value is dropped, the regions that its destructor
+// declares `#[may_dangle]` (directly, or through a type parameter) are
+// not required to be live, while the other regions in its type are.
+
+// compile-flags:-Zborrowck=compare -Znll
+
+#![allow(warnings)]
+#![feature(dropck_eyepatch)]
+#![feature(generic_param_attrs)]
+
+fn use_x(_: usize) -> bool { true }
+
+fn main() {
+}
+
+struct Wrap<T> {
+    value: T,
+}
+
+impl<T> Drop for Wrap<T> {
+    fn drop(&mut self) { }
+}
+
+struct WrapType<T> {
+    value: T,
+}
+
+unsafe impl<#[may_dangle] T> Drop for WrapType<T> {
+    fn drop(&mut self) { }
+}
+
+struct WrapRegion<'a> {
+    value: &'a usize,
+}
+
+unsafe impl<#[may_dangle] 'a> Drop for WrapRegion<'a> {
+    fn drop(&mut self) { }
+}
+
+struct Pair<'a, 'b> {
+    dangling: &'a usize,
+    used: &'b usize,
+}
+
+unsafe impl<#[may_dangle] 'a, 'b> Drop for Pair<'a, 'b> {
+    fn drop(&mut self) { }
+}
+
+fn no_may_dangle() {
+    let mut v = [1, 2, 3];
+    let p = Wrap { value: &v[0] };
+    use_x(*p.value);
+    v[0] = 4;
+    //~^ ERROR (Ast) [E0506]
+    //~| ERROR (Mir) [E0506]
+}
+
+fn may_dangle_type() {
+    let mut v = [1, 2, 3];
+    let p = WrapType { value: &v[0] };
+    use_x(*p.value);
+    v[0] = 4;
+    //~^ ERROR (Ast) [E0506]
+}
+
+fn may_dangle_region() {
+    let mut v = [1, 2, 3];
+    let p = WrapRegion { value: &v[0] };
+    use_x(*p.value);
+    v[0] = 4;
+    //~^ ERROR (Ast) [E0506]
+}
+
+fn may_dangle_one_region() {
+    let mut v = [1, 2, 3];
+    let mut w = [1, 2, 3];
+    let p = Pair { dangling: &v[0], used: &w[0] };
+    use_x(*p.dangling + *p.used);
+    v[0] = 4;
+    //~^ ERROR (Ast) [E0506]
+    w[0] = 4;
+    //~^ ERROR (Ast) [E0506]
+    //~| ERROR (Mir) [E0506]
+}
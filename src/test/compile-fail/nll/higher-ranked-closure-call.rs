@@ -0,0 +1,37 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// Test that calling a closure with a higher-ranked signature
+// (`for<'a> Fn(&'a u32) -> &'a u32`) instantiates its late-bound
+// region afresh at each call, so that the borrow passed to one call
+// is only bounded by the uses of that call's result.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+fn use_ref(_: &u32) { }
+
+fn nll_fail<F>(f: F) where F: for<'a> Fn(&'a u32) -> &'a u32 {
+    let mut x = 22;
+    let r = f(&x);
+    x = 23; //~ ERROR cannot assign to `x` because it is borrowed [E0506]
+    use_ref(r);
+}
+
+fn nll_ok<F>(f: F) where F: for<'a> Fn(&'a u32) -> &'a u32 {
+    let mut x = 22;
+    let r = f(&x);
+    use_ref(r);
+    x = 23;
+    let s = f(&x);
+    use_ref(s);
+}
+
+fn main() { }
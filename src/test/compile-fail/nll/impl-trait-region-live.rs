@@ -0,0 +1,37 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the regions captured by an `impl Trait` value are live
+// for as long as that value is.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+#![feature(conservative_impl_trait)]
+
+fn elements<'a>(v: &'a Vec<u32>) -> impl Iterator<Item = &'a u32> + 'a {
+    v.iter()
+}
+
+fn nll_fail() {
+    let mut v = vec![1, 2, 3];
+    let iter = elements(&v);
+    v.push(4); //~ ERROR cannot borrow `v` as mutable because it is also borrowed as immutable
+    for _ in iter { }
+}
+
+fn nll_ok() {
+    let mut v = vec![1, 2, 3];
+    let iter = elements(&v);
+    for _ in iter { }
+    v.push(4);
+}
+
+fn main() { }
@@ -0,0 +1,28 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// Test that a reference extracted from a `Result` by the `?` operator
+// is related to the regions in the `Result` like any other, so that
+// reborrowing through it is bounded by the outer reference.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+fn get_ok<'a, 'b>(x: Result<&'a mut &'b mut u32, ()>) -> Result<&'a mut u32, ()> {
+    let r = x?;
+    Ok(&mut **r)
+}
+
+fn get<'a, 'b>(x: Result<&'a mut &'b mut u32, ()>) -> Result<&'b mut u32, ()> {
+    let r = x?;
+    Ok(&mut **r) //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn main() { }
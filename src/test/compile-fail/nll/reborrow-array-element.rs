@@ -0,0 +1,35 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// Test that borrowing an element of an array reached through a
+// reference (`&mut (*arr)[i]` where `arr: &mut [T; N]`) is bounded by
+// the region of that reference, and of any reference it is reached
+// through in turn.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+fn element_ok<'a>(arr: &'a mut [u32; 4], i: usize) -> &'a mut u32 {
+    &mut (*arr)[i]
+}
+
+fn element<'a, 'b>(arr: &'a mut [u32; 4], i: usize) -> &'b mut u32 {
+    &mut (*arr)[i] //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn nested_ok<'a, 'b>(arr: &'a mut &'b mut [u32; 4], i: usize) -> &'a mut u32 {
+    &mut (**arr)[i]
+}
+
+fn nested<'a, 'b>(arr: &'a mut &'b mut [u32; 4], i: usize) -> &'b mut u32 {
+    &mut (**arr)[i] //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn main() { }
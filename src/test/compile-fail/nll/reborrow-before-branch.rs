@@ -0,0 +1,29 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a reborrow occurring just before a branch constrains the
+// borrow region along both arms of the branch. The constraint is added
+// at the terminator of the borrow's block, and flows from there into
+// each successor.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+fn either<'a, 'b>(x: &'a mut &'b mut u32, c: bool) -> &'b mut u32 {
+    let r = &mut **x; //~ ERROR free region `'a` does not outlive `'b`
+    if c {
+        r
+    } else {
+        r
+    }
+}
+
+fn main() { }
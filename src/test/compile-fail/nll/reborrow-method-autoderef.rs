@@ -0,0 +1,28 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the reborrow introduced by auto-deref in a method call
+// through two levels of references (`x.first_mut()` is lowered to
+// `<[u32]>::first_mut(&mut **x)`) relates the regions of both
+// references to the region of the receiver.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+fn ok<'a, 'b>(x: &'a mut &'b mut [u32]) -> Option<&'a mut u32> {
+    x.first_mut()
+}
+
+fn too_long<'a, 'b>(x: &'a mut &'b mut [u32]) -> Option<&'b mut u32> {
+    x.first_mut() //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn main() { }
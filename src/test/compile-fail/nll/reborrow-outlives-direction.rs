@@ -0,0 +1,35 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that, for both shared and mutable reborrows, it is the region
+// of the reference being reborrowed that must outlive the region of
+// the new borrow (and not the other way around).
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+fn shared_ok<'a, 'b: 'a>(x: &'b u32) -> &'a u32 {
+    &*x
+}
+
+fn shared<'a, 'b>(x: &'a u32) -> &'b u32 {
+    &*x //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn mutable_ok<'a, 'b: 'a>(x: &'b mut u32) -> &'a mut u32 {
+    &mut *x
+}
+
+fn mutable<'a, 'b>(x: &'a mut u32) -> &'b mut u32 {
+    &mut *x //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn main() { }
@@ -0,0 +1,28 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// Test that borrowing an element through an overloaded `IndexMut` of
+// a collection reached through a reference (`&mut (*v)[i]` where `v:
+// &mut Vec<u32>`) is bounded by the region of that reference. The
+// index lowers to a call of `index_mut`, and the element is borrowed
+// through a `Deref` of its result.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+fn element_ok<'a, 'b>(v: &'a mut &'b mut Vec<u32>, i: usize) -> &'a mut u32 {
+    &mut (**v)[i]
+}
+
+fn element<'a, 'b>(v: &'a mut &'b mut Vec<u32>, i: usize) -> &'b mut u32 {
+    &mut (**v)[i] //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn main() { }
@@ -0,0 +1,27 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that reborrowing through a shared reference to a mutable
+// reference (`&**x` where `x: &&mut T`) walks past the inner `&mut`
+// and still requires the outer shared region to outlive the borrow.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+fn ok<'a, 'b>(x: &'a &'b mut u32) -> &'a u32 {
+    &**x
+}
+
+fn too_long<'a, 'b>(x: &'a &'b mut u32) -> &'b u32 {
+    &**x //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn main() { }
@@ -0,0 +1,26 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that reborrowing through a shared reference still requires
+// the region of that reference to outlive the new borrow.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+fn shallow<'a, 'b>(x: &'a &'b u32) -> &'b &'b u32 {
+    &*x //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn deep<'a, 'b>(x: &'a &'b u32) -> &'b u32 {
+    &**x // OK: only `'b` must outlive the borrow
+}
+
+fn main() { }
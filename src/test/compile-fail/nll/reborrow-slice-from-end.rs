@@ -0,0 +1,39 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that borrowing an element counted from the end of a slice
+// (`ConstantIndex { from_end: true, .. }`), or indexed by a computed
+// index, is bounded by the references the slice is reached through.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+#![feature(slice_patterns)]
+
+fn second_last_ok<'a, 'b>(s: &'a mut &'b mut [u32]) -> &'a mut u32 {
+    match **s {
+        [.., ref mut y, _] => y,
+        _ => panic!(),
+    }
+}
+
+fn second_last<'a, 'b>(s: &'a mut &'b mut [u32]) -> &'b mut u32 {
+    match **s {
+        [.., ref mut y, _] => y, //~ ERROR free region `'a` does not outlive `'b`
+        _ => panic!(),
+    }
+}
+
+fn last_index<'a, 'b>(s: &'a mut &'b mut [u32]) -> &'b mut u32 {
+    let len = s.len();
+    &mut (**s)[len - 1] //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn main() { }
@@ -0,0 +1,48 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that borrows created by slice patterns (which use the
+// `ConstantIndex` and `Subslice` projections) are bounded by the
+// references that the slice is reached through.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+#![feature(slice_patterns)]
+
+fn first_ok<'a, 'b>(s: &'a mut &'b mut [u32]) -> &'a mut u32 {
+    match **s {
+        [ref mut a, ..] => a,
+        _ => panic!(),
+    }
+}
+
+fn first<'a, 'b>(s: &'a mut &'b mut [u32]) -> &'b mut u32 {
+    match **s {
+        [ref mut a, ..] => a, //~ ERROR free region `'a` does not outlive `'b`
+        _ => panic!(),
+    }
+}
+
+fn last<'a, 'b>(s: &'a mut &'b mut [u32]) -> &'b mut u32 {
+    match **s {
+        [.., ref mut z] => z, //~ ERROR free region `'a` does not outlive `'b`
+        _ => panic!(),
+    }
+}
+
+fn rest<'a, 'b>(s: &'a mut &'b mut [u32]) -> &'b mut [u32] {
+    match **s {
+        [_, ref mut tail..] => tail, //~ ERROR free region `'a` does not outlive `'b`
+        _ => panic!(),
+    }
+}
+
+fn main() { }
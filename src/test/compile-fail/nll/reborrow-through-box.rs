@@ -0,0 +1,26 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that reborrowing through a `Box` continues on to the reference
+// that the box was reached through.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+fn ok<'a, 'b>(x: &'a mut Box<&'b mut u32>) -> &'a mut u32 {
+    &mut ***x
+}
+
+fn too_long<'a, 'b>(x: &'a mut Box<&'b mut u32>) -> &'b mut u32 {
+    &mut ***x //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn main() { }
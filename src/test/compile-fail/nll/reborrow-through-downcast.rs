@@ -0,0 +1,38 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that borrowing a field of an enum variant that is reached
+// through a reference (which introduces a `Downcast` projection)
+// relates the region of that reference to the new borrow.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+enum Slot<'b> {
+    Full(&'b mut u32),
+    Empty,
+}
+
+fn ok<'a, 'b>(x: &'a mut Slot<'b>) -> &'a mut u32 {
+    match *x {
+        Slot::Full(ref mut r) => &mut **r,
+        Slot::Empty => panic!(),
+    }
+}
+
+fn too_long<'a, 'b>(x: &'a mut Slot<'b>) -> &'b mut u32 {
+    match *x {
+        Slot::Full(ref mut r) => &mut **r, //~ ERROR free region `'a` does not outlive `'b`
+        Slot::Empty => panic!(),
+    }
+}
+
+fn main() { }
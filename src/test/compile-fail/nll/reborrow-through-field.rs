@@ -0,0 +1,35 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that reborrowing a field of a field reached through references
+// relates the regions of each reference along the path to the region
+// of the new borrow.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+struct Inner {
+    y: u32,
+}
+
+struct Outer<'b> {
+    r: &'b mut Inner,
+}
+
+fn ok<'a, 'b>(x: &'a mut Outer<'b>) -> &'a mut u32 {
+    &mut (*(*x).r).y
+}
+
+fn too_long<'a, 'b>(x: &'a mut Outer<'b>) -> &'b mut u32 {
+    &mut (*(*x).r).y //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn main() { }
@@ -0,0 +1,56 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// Test reborrowing through a struct that wraps a mutable reference,
+// in the manner of a pinned reference. Whether the inner reference is
+// reached through the field directly or through an overloaded
+// `DerefMut`, the new borrow must be bounded by the region of the
+// reference to the wrapper.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+use std::ops::{Deref, DerefMut};
+
+struct PinMut<'b, T: 'b> {
+    pointer: &'b mut T,
+}
+
+impl<'b, T> Deref for PinMut<'b, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &*self.pointer
+    }
+}
+
+impl<'b, T> DerefMut for PinMut<'b, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut *self.pointer
+    }
+}
+
+fn field_ok<'a, 'b>(p: &'a mut PinMut<'b, u32>) -> &'a mut u32 {
+    &mut *(*p).pointer
+}
+
+fn field<'a, 'b>(p: &'a mut PinMut<'b, u32>) -> &'b mut u32 {
+    &mut *(*p).pointer //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn deref_ok<'a, 'b>(p: &'a mut PinMut<'b, u32>) -> &'a mut u32 {
+    &mut **p
+}
+
+fn deref<'a, 'b>(p: &'a mut PinMut<'b, u32>) -> &'b mut u32 {
+    &mut **p //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn main() { }
@@ -0,0 +1,39 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that reborrowing through a field whose declared type is an
+// associated type that normalizes to a reference relates the regions
+// of the references along the path to the new borrow.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+trait Pointer {
+    type Target;
+}
+
+impl<'b> Pointer for &'b mut u32 {
+    type Target = &'b mut u32;
+}
+
+struct Holder<P: Pointer> {
+    target: P::Target,
+}
+
+fn ok<'a, 'b>(x: &'a mut Holder<&'b mut u32>) -> &'a mut u32 {
+    &mut *(*x).target
+}
+
+fn too_long<'a, 'b>(x: &'a mut Holder<&'b mut u32>) -> &'b mut u32 {
+    &mut *(*x).target //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn main() { }
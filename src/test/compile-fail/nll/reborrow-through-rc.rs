@@ -0,0 +1,30 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that borrowing through an `Rc` that is itself behind a
+// reference relates the region of that reference to the region of
+// the new borrow.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+fn ok<'a>(x: &'a Rc<Cell<u32>>) -> &'a Cell<u32> {
+    &**x
+}
+
+fn too_long<'a, 'b>(x: &'a Rc<Cell<u32>>) -> &'b Cell<u32> {
+    &**x //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn main() { }
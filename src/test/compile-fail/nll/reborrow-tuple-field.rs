@@ -0,0 +1,27 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that reborrowing a field of a tuple reached through a mutable
+// reference (`&mut (*t).1`) walks through the `Field` projection to
+// the `Deref`, and requires that reference to outlive the new borrow.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+fn ok<'a>(t: &'a mut (u32, u32)) -> &'a mut u32 {
+    &mut (*t).1
+}
+
+fn too_long<'a, 'b>(t: &'a mut (u32, u32)) -> &'b mut u32 {
+    &mut (*t).1 //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn main() { }
@@ -0,0 +1,36 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// Test that `-Z nll-report-hot-regions` warns about a region that is
+// live across most of a function, but not about one that is only
+// live briefly.
+
+// compile-flags:-Znll -Zborrowck=mir -Znll-report-hot-regions=8
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+fn hot(x: u32) -> u32 { //~ WARN is live at
+    let r = &x;
+    let a = *r + 1;
+    let b = *r + a;
+    let c = *r + b;
+    let d = *r + c;
+    let e = *r + d;
+    let f = *r + e;
+    *r + f
+}
+
+fn cold(x: u32) -> u32 {
+    let r = &x;
+    *r + 1
+}
+
+#[rustc_error]
+fn main() { } //~ ERROR compilation successful
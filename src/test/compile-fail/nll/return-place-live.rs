@@ -0,0 +1,29 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that returning a reborrow relates its region to the free region
+// of the return type, even though the `Return` terminator does not
+// mention the return place itself.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+fn ok<'a>(x: &'a mut u32) -> &'a u32 {
+    let r = &*x;
+    r
+}
+
+fn too_long<'a, 'b>(x: &'a mut u32) -> &'b u32 {
+    let r = &*x;
+    r //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn main() { }
@@ -0,0 +1,38 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// Test that switching directly on a place behind a reference keeps
+// the region of that reference live at the switch, even when no
+// branch uses the reference again.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+fn nll_fail_int() {
+    let mut x = 1;
+    let r = &x;
+    x = 2; //~ ERROR cannot assign to `x` because it is borrowed [E0506]
+    match *r {
+        1 => { }
+        _ => { }
+    }
+}
+
+fn nll_ok() {
+    let mut x = 1;
+    let r = &x;
+    match *r {
+        1 => { }
+        _ => { }
+    }
+    x = 2;
+}
+
+fn main() { }
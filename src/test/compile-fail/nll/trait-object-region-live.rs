@@ -0,0 +1,31 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the region bound of a trait object (`'a` in
+// `Box<Debug + 'a>`) is constrained by the uses of a local of that
+// type, just like any other region in the local's type.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+use std::fmt::Debug;
+
+fn ok<'a>(x: &'a u32) -> Box<Debug + 'a> {
+    let b: Box<Debug + 'a> = Box::new(x);
+    b
+}
+
+fn too_long<'a, 'b>(x: &'a u32) -> Box<Debug + 'b> {
+    let b: Box<Debug + 'a> = Box::new(x);
+    b //~ ERROR free region `'a` does not outlive `'b`
+}
+
+fn main() { }
@@ -0,0 +1,31 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that `-Z nll-warn-unconstrained` warns about a lifetime
+// parameter that nothing in the function body constrains, but not
+// about one that appears in the type of a live argument.
+
+// compile-flags:-Znll -Zborrowck=mir -Znll-warn-unconstrained
+
+#![allow(warnings)]
+#![feature(rustc_attrs)]
+
+use std::marker::PhantomData;
+
+struct Foo<'a>(PhantomData<&'a ()>);
+
+impl<'a> Foo<'a> {
+    fn phantom(x: u32) -> u32 { x } //~ WARN lifetime `'a` is not constrained
+}
+
+fn used<'b>(x: &'b u32) -> u32 { *x }
+
+#[rustc_error]
+fn main() { } //~ ERROR compilation successful
@@ -0,0 +1,34 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the region of a reference that is cast to a raw pointer
+// includes the point of the cast (`bb0[8]`), and no points after it.
+
+// compile-flags:-Znll -Zverbose
+//                     ^^^^^^^^^ force compiler to dump more region information
+
+#![allow(warnings)]
+
+fn main() {
+    let mut x: u32 = 22;
+    let p = &mut x as *mut u32;
+}
+
+// END RUST SOURCE
+// START rustc.main.nll.0.mir
+// | '_#1r: {bb0[7], bb0[8]}
+// | '_#2r: {bb0[8]}
+// | '_#3r: {bb0[8]}
+// | '_#4r: {bb0[7], bb0[8]}
+// END rustc.main.nll.0.mir
+// START rustc.main.nll.0.mir
+//             | Live variables at bb0[8]: [_4]
+//         _3 = move _4 as *mut u32 (Misc);
+// END rustc.main.nll.0.mir
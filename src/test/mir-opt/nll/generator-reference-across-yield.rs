@@ -0,0 +1,39 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a reference held by a generator across a `yield` keeps
+// its region live at the suspension point (`bb0[4]`) and in the
+// resume block, up to its last use.
+//
+// The reference points into a static so that the type of the
+// generator (which records the types held across the `yield`) only
+// mentions `'static`.
+
+// compile-flags:-Znll -Zverbose
+//                     ^^^^^^^^^ force compiler to dump more region information
+
+#![allow(warnings)]
+#![feature(generators)]
+
+static X: i32 = 22;
+
+fn main() {
+    let _gen = || {
+        let r: &'static i32 = &X;
+        yield;
+        let _y = *r;
+    };
+}
+
+// END RUST SOURCE
+// START rustc.main-{{closure}}.nll.0.mir
+// | '_#1r: {bb0[2], bb0[3], bb0[4], bb1[0], bb1[1], bb1[2], bb1[3]}
+// | '_#2r: {bb0[2], bb0[3], bb0[4], bb1[0], bb1[1], bb1[2], bb1[3]}
+// END rustc.main-{{closure}}.nll.0.mir
@@ -0,0 +1,41 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that yielding a reference from a generator keeps the region of
+// the reference live across the suspension, until the caller is done
+// with the yielded value: the yield type is `&'static i32`, so the
+// region of the yielded temporary (`'_#3r`) reaches from the `yield`
+// (`bb0[4]`) to the end of the generator, and outlives `'static`.
+
+// compile-flags:-Znll -Zverbose
+//                     ^^^^^^^^^ force compiler to dump more region information
+
+#![allow(warnings)]
+#![feature(generators)]
+
+static X: i32 = 22;
+
+fn main() {
+    let _gen = || {
+        let r: &'static i32 = &X;
+        yield r;
+    };
+}
+
+// END RUST SOURCE
+// START rustc.main-{{closure}}.nll.0.mir
+// | '_#1r: {bb0[2], bb0[3], bb0[4], bb1[0], bb1[1], bb1[2], bb1[3], bb1[4], '_#0r}
+// | '_#2r: {bb0[2], bb0[3], bb0[4], bb1[0], bb1[1], bb1[2], bb1[3], bb1[4], '_#0r}
+// | '_#3r: {bb0[4], bb1[0], bb1[1], bb1[2], bb1[3], bb1[4], '_#0r}
+// END rustc.main-{{closure}}.nll.0.mir
+// START rustc.main-{{closure}}.nll.0.mir
+//             | Live variables at bb0[4]: [_4]
+//         _1 = suspend(move _4) -> bb1;
+// END rustc.main-{{closure}}.nll.0.mir
@@ -0,0 +1,40 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test the outlives constraints that are dumped after the MIR. The
+// subtyping constraints require each borrow region to outlive the
+// region in the type of the variable it is stored in (`'_#1r: '_#3r`
+// and `'_#2r: '_#4r`), and the reborrow `&mut *r_a` requires the
+// region in the type of `r_a` to outlive the reborrow region
+// (`'_#3r: '_#2r`).
+
+// compile-flags:-Znll -Zverbose
+//                     ^^^^^^^^^ force compiler to dump more region information
+
+#![allow(warnings)]
+
+fn main() {
+    let mut foo: i32 = 22;
+    let r_a = &mut foo;
+    let r_b = &mut *r_a;
+    *r_b = 23;
+}
+
+// END RUST SOURCE
+// START rustc.main.nll.0.mir
+// | '_#1r: '_#3r @ bb0[4]
+// | '_#2r: '_#4r @ bb0[6]
+// | '_#3r: '_#2r @ bb0[6]
+// END rustc.main.nll.0.mir
+// START rustc.main.nll.0.mir
+// let _2: &'_#3r mut i32;
+// ...
+// let _3: &'_#4r mut i32;
+// END rustc.main.nll.0.mir
@@ -0,0 +1,41 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the regions in the type of a promoted constant are live
+// at the point where the constant is used: the region of `&22`
+// (`'_#1r`) includes `bb0[1]`, where the promoted constant is
+// assigned to `x`, as well as the points where `x` is live.
+
+// compile-flags:-Znll -Zverbose
+//                     ^^^^^^^^^ force compiler to dump more region information
+
+#![allow(warnings)]
+
+fn use_x(_: &u32) -> bool { true }
+
+fn main() {
+    let x: &u32 = &22;
+    use_x(x);
+}
+
+// END RUST SOURCE
+// START rustc.main.nll.0.mir
+// | '_#1r: {bb0[1], bb0[2], bb0[3]}
+// | '_#2r: {bb0[2], bb0[3]}
+// ...
+//             let _1: &'_#2r u32;
+// END rustc.main.nll.0.mir
+// START rustc.main.nll.0.mir
+//     bb0: {
+//             | Live variables at bb0[0]: []
+//         StorageLive(_1);
+//             | Live variables at bb0[1]: []
+//         _1 = promoted[0];
+// END rustc.main.nll.0.mir
@@ -0,0 +1,41 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that reborrowing a field reached through three layers of
+// `&mut` relates the region of every layer (`'_#1r`, `'_#2r` and
+// `'_#3r`) to the region of the new borrow (`'_#4r`). The walk over
+// the projections goes from the outside in, through the field and
+// each dereference in turn.
+
+// compile-flags:-Znll -Zverbose
+//                     ^^^^^^^^^ force compiler to dump more region information
+
+#![allow(warnings)]
+
+fn second<'a, 'b, 'c>(x: &'a mut &'b mut &'c mut (u32, u32)) -> &'a mut u32 {
+    &mut (***x).1
+}
+
+fn main() {
+    let mut pair = (1, 2);
+    let mut r1 = &mut pair;
+    let mut r2 = &mut r1;
+    *second(&mut r2) += 10;
+}
+
+// END RUST SOURCE
+// START rustc.second.nll.0.mir
+// | '_#1r: '_#4r @ bb0[2]
+// | '_#2r: '_#4r @ bb0[2]
+// | '_#3r: '_#4r @ bb0[2]
+// | '_#4r: '_#6r @ bb0[2]
+// | '_#5r: '_#1r @ bb0[3]
+// | '_#6r: '_#5r @ bb0[3]
+// END rustc.second.nll.0.mir
@@ -0,0 +1,37 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that reborrowing an element of a slice that is reached
+// through two layers of `&mut` relates the regions of both layers
+// (`'_#1r` and `'_#2r`) to the region of the new borrow (`'_#3r`).
+
+// compile-flags:-Znll -Zverbose
+//                     ^^^^^^^^^ force compiler to dump more region information
+
+#![allow(warnings)]
+
+fn first<'a, 'b>(v: &'a mut &'b mut [u32]) -> &'a mut u32 {
+    &mut (**v)[0]
+}
+
+fn main() {
+    let mut data = [1, 2, 3];
+    let mut slice: &mut [u32] = &mut data;
+    *first(&mut slice) += 10;
+}
+
+// END RUST SOURCE
+// START rustc.first.nll.0.mir
+// | '_#1r: '_#3r @ bb2[1]
+// | '_#2r: '_#3r @ bb2[1]
+// | '_#3r: '_#5r @ bb2[1]
+// | '_#4r: '_#1r @ bb2[2]
+// | '_#5r: '_#4r @ bb2[2]
+// END rustc.first.nll.0.mir
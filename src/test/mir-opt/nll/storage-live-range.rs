@@ -0,0 +1,43 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a reference local declared inside a loop is not considered
+// live outside of its storage range: the regions of `r` contain only
+// the points between its initialization and its last use, and neither
+// the assignment to `x` at the top of the loop (`bb2[0]`) nor the
+// points after the call to `use_x`.
+
+// compile-flags:-Znll -Zverbose
+//                     ^^^^^^^^^ force compiler to dump more region information
+
+#![allow(warnings)]
+
+fn use_x(_: &u32) -> bool { true }
+
+fn main() {
+    let mut x: u32 = 0;
+    loop {
+        x = 1;
+        let r = &x;
+        use_x(r);
+    }
+}
+
+// END RUST SOURCE
+// START rustc.main.nll.0.mir
+// | '_#1r: {bb2[3], bb2[4]}
+// | '_#2r: {bb2[3], bb2[4]}
+// | '_#3r: {bb2[5]}
+// END rustc.main.nll.0.mir
+// START rustc.main.nll.0.mir
+//     bb2: {
+//             | Live variables at bb2[0]: []
+//         _1 = const 1u32;
+// END rustc.main.nll.0.mir
@@ -0,0 +1,28 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn empty() {}
+
+fn one_borrow() {
+    let x = 22;
+    let _y = &x;
+}
+
+fn two_borrows() {
+    let x = 22;
+    let _y = &x;
+    let _z = &x;
+}
+
+fn main() {
+    empty();
+    one_borrow();
+    two_borrows();
+}
@@ -0,0 +1,140 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Writes `chains.rs`, a library of functions that each borrow a place
+// reached from their argument through a random chain of projections,
+// and `expected.txt`, which gives the number of reborrow constraints
+// that each of these functions should give rise to. The chains only
+// depend on `SEED`, so a failure can be reproduced.
+
+use std::env;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+const SEED: u64 = 0x5eed;
+
+/// The number of functions to generate.
+const FUNCTIONS: usize = 200;
+
+/// The maximum number of layers in the type of an argument.
+const MAX_LAYERS: u64 = 8;
+
+/// A linear congruential generator, which is all we need here.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: u64) -> u64 {
+        self.0 = self.0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % n
+    }
+}
+
+/// A layer of the type of an argument, and the projection that gets
+/// through it.
+#[derive(Copy, Clone)]
+enum Layer {
+    /// `&mut T`, projected with `Deref`.
+    MutRef,
+    /// `&T`, projected with `Deref`.
+    SharedRef,
+    /// `Box<T>`, projected with `Deref`.
+    Box,
+    /// `(T, u8)`, projected with `Field`.
+    Field,
+    /// `[T; 2]`, projected with `Index`.
+    Index,
+}
+
+fn main() {
+    let dir = env::args().nth(1).unwrap();
+    let dir = Path::new(&dir);
+    let mut chains = File::create(dir.join("chains.rs")).unwrap();
+    let mut expected = File::create(dir.join("expected.txt")).unwrap();
+    let mut rng = Rng(SEED);
+
+    writeln!(chains, "#![allow(warnings)]").unwrap();
+    for i in 0..FUNCTIONS {
+        // The first layer is the outermost one of the argument's type,
+        // so its projection is the first to be applied.
+        let len = 1 + rng.below(MAX_LAYERS);
+        let layers: Vec<Layer> = (0..len)
+            .map(|_| match rng.below(5) {
+                0 => Layer::MutRef,
+                1 => Layer::SharedRef,
+                2 => Layer::Box,
+                3 => Layer::Field,
+                _ => Layer::Index,
+            })
+            .collect();
+
+        // If the innermost type is an `Option`, the borrow is made by a
+        // `ref` binding, which adds a `Downcast` projection.
+        let downcast = rng.below(2) == 0;
+
+        let mut ty = String::from(if downcast { "Option<u32>" } else { "u32" });
+        for layer in layers.iter().rev() {
+            ty = match *layer {
+                Layer::MutRef => format!("&mut {}", ty),
+                Layer::SharedRef => format!("& {}", ty),
+                Layer::Box => format!("Box<{}>", ty),
+                Layer::Field => format!("({}, u8)", ty),
+                Layer::Index => format!("[{}; 2]", ty),
+            };
+        }
+
+        let mut place = String::from("x");
+        for layer in &layers {
+            place = match *layer {
+                Layer::MutRef | Layer::SharedRef | Layer::Box => format!("(*{})", place),
+                Layer::Field => format!("({}).0", place),
+                Layer::Index => format!("{}[i]", place),
+            };
+        }
+
+        // The reborrow constraints are added while walking the
+        // projections from the outside in, that is, from the last layer
+        // to the first. Every reference gives one constraint, but the
+        // walk stops after the first shared reference.
+        let mut edges = 0;
+        for layer in layers.iter().rev() {
+            match *layer {
+                Layer::MutRef => edges += 1,
+                Layer::SharedRef => {
+                    edges += 1;
+                    break;
+                }
+                Layer::Box | Layer::Field | Layer::Index => {}
+            }
+        }
+
+        if downcast {
+            writeln!(
+                chains,
+                "pub fn f{}(x: {}, i: usize) -> u32 {{ \
+                 match {} {{ Some(ref y) => *y, None => 0 }} }}",
+                i,
+                ty,
+                place
+            ).unwrap();
+        } else {
+            writeln!(
+                chains,
+                "pub fn f{}(x: {}, i: usize) -> u32 {{ let r = &{}; *r }}",
+                i,
+                ty,
+                place
+            ).unwrap();
+        }
+        writeln!(expected, "f{} {}", i, edges).unwrap();
+    }
+}
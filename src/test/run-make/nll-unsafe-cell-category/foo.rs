@@ -0,0 +1,30 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::cell::{Cell, UnsafeCell};
+
+fn foo<'a>(cell: &'a UnsafeCell<u32>, x: &'a &'a u32) -> (&'a UnsafeCell<u32>, &'a u32) {
+    (&*cell, &**x)
+}
+
+fn bar<'a>(cell: &'a &'a Cell<u32>) -> &'a Cell<u32> {
+    &**cell
+}
+
+fn main() {
+    let cell = UnsafeCell::new(22);
+    let v = 44;
+    let r = &v;
+    let (c, x) = foo(&cell, &r);
+    unsafe { *c.get() += *x; }
+    let cell = Cell::new(1);
+    let r = &cell;
+    bar(&r).set(2);
+}
@@ -0,0 +1,31 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test drop-liveness for a recursive type whose dtorck constraint
+// mentions the same associated type more than once: the regions in
+// the normalized type must be live wherever the value may be dropped.
+
+// compile-flags:-Zborrowck=mir -Znll -Zverify-nll-regions
+
+struct Node<I: Iterator> {
+    first: Option<I::Item>,
+    last: Option<I::Item>,
+    next: Option<Box<Node<I>>>,
+}
+
+fn main() {
+    let data = vec![String::from("a"), String::from("b")];
+    let node: Node<std::slice::Iter<String>> = Node {
+        first: data.iter().next(),
+        last: data.iter().last(),
+        next: Some(Box::new(Node { first: None, last: None, next: None })),
+    };
+    assert_eq!(node.first.map(|s| &s[..]), Some("a"));
+}
@@ -0,0 +1,26 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that computing the drop-liveness constraints of a recursive
+// type terminates.
+
+// compile-flags:-Zborrowck=mir -Znll -Zverify-nll-regions
+
+struct S<'a>(&'a u32, Option<Box<S<'a>>>);
+
+impl<'a> Drop for S<'a> {
+    fn drop(&mut self) { }
+}
+
+fn main() {
+    let x = 22;
+    let s = S(&x, Some(Box::new(S(&x, None))));
+    assert_eq!(*s.0, 22);
+}
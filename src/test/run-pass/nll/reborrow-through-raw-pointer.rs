@@ -0,0 +1,34 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test reborrowing through a raw pointer that wraps a mutable
+// reference, where the raw pointer is itself reached through a
+// reference.
+
+// compile-flags:-Zborrowck=mir -Znll -Zverify-nll-regions
+
+struct Holder<'b> {
+    ptr: *mut &'b mut u32,
+}
+
+fn get<'a, 'b>(h: &'a mut Holder<'b>) -> &'a mut u32 {
+    unsafe { &mut **(*h).ptr }
+}
+
+fn main() {
+    let mut value = 1;
+    let mut r: &mut u32 = &mut value;
+    {
+        let mut holder = Holder { ptr: &mut r };
+        *get(&mut holder) += 1;
+    }
+    *r += 1;
+    assert_eq!(value, 3);
+}
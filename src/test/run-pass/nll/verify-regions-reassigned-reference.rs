@@ -0,0 +1,32 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that `-Z verify-nll-regions` accepts an outlives constraint
+// whose `sub` region contains points that are not reachable from the
+// point of the constraint. The region in the type of `r` is live
+// while `r` refers to `x`, but the borrow of `y` (whose region must
+// outlive it from the reassignment onwards) does not include those
+// earlier points.
+
+// compile-flags:-Zborrowck=mir -Znll -Zverify-nll-regions
+
+fn use_ref(r: &u32) -> u32 {
+    *r
+}
+
+fn main() {
+    let x = 22;
+    let y = 23;
+    let mut r = &x;
+    let a = use_ref(r);
+    r = &y;
+    let b = use_ref(r);
+    assert_eq!(a + b, 45);
+}
@@ -0,0 +1,65 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that streaming the constraints of each function into a custom
+// `ConstraintSink` gives the same live points and outlives constraints
+// as generating them into the region inference context, and that a
+// `ConstraintGeneration` constructed by hand and driven through its
+// borrow phase alone gives the same outlives constraints. This covers
+// the liveness, drop-liveness and reborrow constraints.
+
+// compile-flags:-Zborrowck=mir -Znll -Znll-verify-stream
+
+struct Guard<'a> {
+    counter: &'a mut u32,
+}
+
+impl<'a> Drop for Guard<'a> {
+    fn drop(&mut self) {
+        *self.counter += 1;
+    }
+}
+
+fn first<'a>(v: &'a mut Vec<u32>) -> &'a mut u32 {
+    &mut v[0]
+}
+
+fn pick<'a>(x: &'a mut u32, y: &'a mut u32, c: bool) -> &'a mut u32 {
+    let r = if c { &mut *x } else { &mut *y };
+    r
+}
+
+fn pair<'a>(x: &'a u32) -> (&'a u32, &'a u32) {
+    (x, x)
+}
+
+fn main() {
+    let mut v = vec![1, 2, 3];
+    *first(&mut v) += 1;
+
+    let mut a = 1;
+    let mut b = 2;
+    for i in 0..3 {
+        *pick(&mut a, &mut b, i % 2 == 0) += 1;
+    }
+
+    let (p, q) = pair(&v[1]);
+    let sum = *p + *q;
+
+    let mut count = 0;
+    {
+        let _guard = Guard { counter: &mut count };
+    }
+
+    assert_eq!(v[0], 2);
+    assert_eq!(a + b, 6);
+    assert_eq!(sum, 4);
+    assert_eq!(count, 1);
+}
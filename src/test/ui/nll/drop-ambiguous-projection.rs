@@ -0,0 +1,31 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a failure to normalize a type in the drop-check rules of
+// an argument is reported at the drop of that argument, inside the
+// function it belongs to.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+trait Tr {
+    type A;
+}
+
+struct S<T: Tr>(T::A);
+
+impl<T: Tr> Drop for S<T> {
+    fn drop(&mut self) { }
+}
+
+fn consume<T>(_s: S<T>) where T: Tr<A = u32>, T: Tr<A = i32> {
+    //~^ ERROR type annotations required
+}
+
+fn main() { }
@@ -0,0 +1,37 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that failing to normalize a type in the drop-check rules of a
+// value (here, because of the conflicting bounds on `consume`) does
+// not add any diagnostics of its own once errors have already been
+// reported for an earlier function.
+
+// compile-flags:-Znll
+
+fn foo<'a, 'b>(x: &'a u32, y: &'b u32) -> &'b u32 {
+    &*x //~ ERROR free region `'a` does not outlive `'b`
+    //~^ ERROR `*x` does not live long enough
+    //~| WARN not reporting region error due to -Znll
+}
+
+trait Tr {
+    type A;
+}
+
+struct S<T: Tr>(T::A);
+
+impl<T: Tr> Drop for S<T> {
+    fn drop(&mut self) { }
+}
+
+fn consume<T>(_s: S<T>) where T: Tr<A = u32>, T: Tr<A = i32> {
+}
+
+fn main() { }
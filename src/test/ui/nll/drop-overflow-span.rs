@@ -0,0 +1,39 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that an overflow while computing the drop-liveness constraints
+// of an argument is reported at the drop of that argument. Arguments
+// are not checked by the AST-based dropck, so only NLL reports this.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(dead_code)]
+
+use std::marker::PhantomData;
+
+struct Digit<T> {
+    elem: T
+}
+
+struct Node<T: 'static> { m: PhantomData<&'static T> }
+
+enum FingerTree<T: 'static> {
+    Single(T),
+    Deep(
+        Box<FingerTree<Node<T>>>,
+        Digit<T>,
+        )
+}
+
+fn consume(_tree: FingerTree<u32>) {
+    //~^ ERROR overflow while adding drop-check rules for FingerTree
+}
+
+fn main() { }
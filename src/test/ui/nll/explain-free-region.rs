@@ -0,0 +1,24 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that `-Z nll-explain` notes the chain of outlives constraints
+// that carried the offending free region into the region that the
+// erroneous constraint applies to: `'b` flows into the region of the
+// reborrow `&*z`, and from there into the region in the type of `z`.
+
+// compile-flags:-Znll -Zborrowck=mir -Znll-explain
+
+fn foo<'a, 'b>(x: &'a u32, _y: &'b u32) -> &'b u32 {
+    let z: &'a u32 = x; //~ ERROR free region `'a` does not outlive `'b`
+    &*z
+    //~^ WARN not reporting region error due to -Znll
+}
+
+fn main() { }
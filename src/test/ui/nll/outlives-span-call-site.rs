@@ -0,0 +1,33 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that when the same outlives constraint arises both inside a
+// macro expansion and in the user's code, the span from the user's
+// code is the one kept. Both calls to `g` require the region in the
+// type of `t` to outlive `'static` at the same point (the join after
+// the `if`), and the note points at the call outside the macro.
+
+// compile-flags:-Znll -Zborrowck=mir -Znll-explain
+
+fn h(_: &'static u32) {}
+
+fn g() -> fn(&'static u32) { h }
+
+macro_rules! call_g {
+    () => { g() }
+}
+
+fn foo<'a>(c: bool) -> fn(&'a u32) {
+    let t = if c { call_g!() } else { g() };
+    t //~ ERROR free region `'a` does not outlive `'static`
+    //~^ WARN not reporting region error due to -Znll
+}
+
+fn main() { }
@@ -0,0 +1,26 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that an NLL region error caused by a reborrow inside a macro
+// expansion points at the borrow in the macro, with a note at the
+// invocation, rather than at some unrelated constraint.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+macro_rules! reborrow {
+    ($e:expr) => { &*$e } //~ ERROR free region `'a` does not outlive `'b`
+    //~^ WARN not reporting region error due to -Znll
+}
+
+fn foo<'a, 'b>(x: &'a u32, _y: &'b u32) -> &'b u32 {
+    reborrow!(x)
+}
+
+fn main() { }